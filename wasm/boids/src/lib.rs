@@ -40,10 +40,24 @@
 //! const forceY = engine.separation_force_y();
 //! ```
 
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+mod scalar;
 mod simd;
 mod soa;
-
-use soa::{BoidsBuffer, NeighborList};
+#[cfg(all(target_arch = "wasm32", target_os = "wasi"))]
+mod wasi;
+
+// `wasm-bindgen`'s glue assumes a JS host (it imports `__wbindgen_*` shims
+// that only a browser/Node bundler provides) and does not target
+// `wasm32-wasi`. Everything below this point is the browser-facing API;
+// `wasi` holds the plain-C-ABI equivalent used when embedding outside a
+// browser (e.g. wasmtime for server-authoritative simulation or replay).
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
+use simd::{BoundaryMode, WorldBounds};
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
+use soa::{BoidsBuffer, ClusterNeighborList, FlowField, NeighborList, ObstacleList};
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 use wasm_bindgen::prelude::*;
 
 // Use `wee_alloc` as the global allocator for smaller WASM size
@@ -52,6 +66,7 @@ use wasm_bindgen::prelude::*;
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 /// Initialize panic hook for better error messages in browser console
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen(start)]
 pub fn init() {
     #[cfg(feature = "console_error_panic_hook")]
@@ -59,6 +74,7 @@ pub fn init() {
 }
 
 /// Check if WASM SIMD is available
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub fn simd_supported() -> bool {
     simd::simd_available()
@@ -68,13 +84,26 @@ pub fn simd_supported() -> bool {
 ///
 /// Manages memory buffers and provides the interface for JS to
 /// populate unit data and retrieve computed forces.
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub struct BoidsEngine {
     buffer: BoidsBuffer,
     neighbors: NeighborList,
+    cluster_neighbors: ClusterNeighborList,
+    use_cluster_kernel: bool,
+    obstacles: ObstacleList,
     params: simd::BoidsParams,
+    flow_field: Option<FlowField>,
 }
 
+/// Default cap on goal cells seeded per `FlowField::build` call
+pub(crate) const DEFAULT_MAX_GOAL_CELLS: usize = 16;
+
+/// Default cap on static obstacles; generous for typical RTS maps without
+/// forcing every engine instance to size itself to the unit count
+pub(crate) const DEFAULT_MAX_OBSTACLES: usize = 64;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 impl BoidsEngine {
     /// Create a new boids engine with capacity for `max_units`
@@ -83,7 +112,11 @@ impl BoidsEngine {
         Self {
             buffer: BoidsBuffer::new(max_units),
             neighbors: NeighborList::new(max_units),
+            cluster_neighbors: ClusterNeighborList::new(max_units),
+            use_cluster_kernel: false,
+            obstacles: ObstacleList::new(DEFAULT_MAX_OBSTACLES),
             params: simd::BoidsParams::default(),
+            flow_field: None,
         }
     }
 
@@ -150,6 +183,36 @@ impl BoidsEngine {
         self.buffer.layers_ptr()
     }
 
+    /// Get pointer to orientation X array (heading unit vector, read by the
+    /// anisotropic separation kernel -- see `set_anisotropic_separation`)
+    #[wasm_bindgen]
+    pub fn orientation_x_ptr(&self) -> *mut f32 {
+        self.buffer.orientation_x_ptr()
+    }
+
+    /// Get pointer to orientation Y array (heading unit vector)
+    #[wasm_bindgen]
+    pub fn orientation_y_ptr(&self) -> *mut f32 {
+        self.buffer.orientation_y_ptr()
+    }
+
+    /// Get pointer to per-unit length-to-width aspect ratio array
+    /// (kappa; `1.0` or the zeroed default means circular/isotropic)
+    #[wasm_bindgen]
+    pub fn aspect_ratio_ptr(&self) -> *mut f32 {
+        self.buffer.aspect_ratio_ptr()
+    }
+
+    /// Get pointer to the per-unit separation-suppressed override array.
+    /// Write `1` for a unit that died or started gathering since the last
+    /// `build_neighbors`/`build_neighbors_half` call so it's excluded from
+    /// forces immediately instead of waiting for the next rebuild; `0` is
+    /// the default no-op.
+    #[wasm_bindgen]
+    pub fn separation_suppressed_ptr(&self) -> *mut u8 {
+        self.buffer.separation_suppressed_ptr()
+    }
+
     // ==================== Force Output Pointers ====================
 
     /// Get pointer to separation force X array (read after compute)
@@ -188,30 +251,166 @@ impl BoidsEngine {
         self.buffer.force_align_y_ptr()
     }
 
-    // ==================== Neighbor List Pointers ====================
+    /// Get pointer to world-bounds/obstacle steering force X array (read after compute)
+    #[wasm_bindgen]
+    pub fn force_bound_x_ptr(&self) -> *mut f32 {
+        self.buffer.force_bound_x_ptr()
+    }
+
+    /// Get pointer to world-bounds/obstacle steering force Y array (read after compute)
+    #[wasm_bindgen]
+    pub fn force_bound_y_ptr(&self) -> *mut f32 {
+        self.buffer.force_bound_y_ptr()
+    }
+
+    /// Get pointer to flow-field goal-seeking force X array (read after compute)
+    #[wasm_bindgen]
+    pub fn force_seek_x_ptr(&self) -> *mut f32 {
+        self.buffer.force_seek_x_ptr()
+    }
 
-    /// Get pointer to neighbors array (for JS to populate)
+    /// Get pointer to flow-field goal-seeking force Y array (read after compute)
     #[wasm_bindgen]
-    pub fn neighbors_ptr(&mut self) -> *mut u32 {
-        self.neighbors.neighbors_ptr_mut()
+    pub fn force_seek_y_ptr(&self) -> *mut f32 {
+        self.buffer.force_seek_y_ptr()
     }
 
-    /// Get pointer to neighbor offsets array (for JS to populate)
+    // ==================== Obstacles ====================
+
+    /// Get pointer to the packed obstacle `(x, y, radius)` array (for JS to populate)
     #[wasm_bindgen]
-    pub fn neighbor_offsets_ptr(&mut self) -> *mut u32 {
-        self.neighbors.offsets_ptr_mut()
+    pub fn obstacles_ptr(&mut self) -> *mut f32 {
+        self.obstacles.data_ptr_mut()
     }
 
-    /// Get pointer to neighbor counts array (for JS to populate)
+    /// Set the number of obstacles (after JS populates the obstacle array)
     #[wasm_bindgen]
-    pub fn neighbor_counts_ptr(&mut self) -> *mut u32 {
-        self.neighbors.counts_ptr_mut()
+    pub fn set_obstacle_count(&mut self, count: usize) {
+        self.obstacles.set_count(count);
     }
 
-    /// Set total neighbor count (after JS populates neighbor array)
+    // ==================== Flow Field ====================
+
+    /// (Re)create the goal-seeking flow field over a `width x height` grid of
+    /// `cell_size` world units, whose `(0, 0)` cell's min corner sits at
+    /// `(origin_x, origin_y)`. Discards any previously built flow field;
+    /// follow with `flow_field_passable_ptr`/`flow_field_goal_cells_ptr` to
+    /// populate it and `build_flow_field` to run the BFS.
     #[wasm_bindgen]
-    pub fn set_neighbor_total(&mut self, count: usize) {
-        self.neighbors.set_neighbor_count(count);
+    pub fn init_flow_field(&mut self, width: usize, height: usize, cell_size: f32, origin_x: f32, origin_y: f32) {
+        self.flow_field = Some(FlowField::new(
+            width,
+            height,
+            cell_size,
+            origin_x,
+            origin_y,
+            DEFAULT_MAX_GOAL_CELLS,
+        ));
+    }
+
+    /// Get pointer to the row-major passability mask (`!= 0` is passable);
+    /// `None` (no `init_flow_field` call yet) returns null
+    #[wasm_bindgen]
+    pub fn flow_field_passable_ptr(&mut self) -> *mut u8 {
+        self.flow_field
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |f| f.passable_ptr_mut())
+    }
+
+    /// Get pointer to the packed `(x, y)` goal cell array; `None` returns null
+    #[wasm_bindgen]
+    pub fn flow_field_goal_cells_ptr(&mut self) -> *mut u32 {
+        self.flow_field
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |f| f.goal_cells_ptr_mut())
+    }
+
+    /// Set the number of populated goal cells; no-op if `init_flow_field`
+    /// hasn't been called
+    #[wasm_bindgen]
+    pub fn set_flow_field_goal_count(&mut self, count: usize) {
+        if let Some(flow_field) = self.flow_field.as_mut() {
+            flow_field.set_goal_count(count);
+        }
+    }
+
+    /// Run the multi-source BFS across the current passability mask and goal
+    /// cells; no-op if `init_flow_field` hasn't been called
+    #[wasm_bindgen]
+    pub fn build_flow_field(&mut self) {
+        if let Some(flow_field) = self.flow_field.as_mut() {
+            flow_field.build();
+        }
+    }
+
+    /// Discard the flow field; units receive zero seek force until
+    /// `init_flow_field` is called again
+    #[wasm_bindgen]
+    pub fn clear_flow_field(&mut self) {
+        self.flow_field = None;
+    }
+
+    /// Set the magnitude of the goal-seeking steering force (0 disables it)
+    #[wasm_bindgen]
+    pub fn set_seek_strength(&mut self, strength: f32) {
+        self.params.seek_strength = strength;
+    }
+
+    // ==================== Neighbor List ====================
+
+    /// Build the neighbor list from the current positions/radii/layers using
+    /// an internal uniform spatial hash grid.
+    ///
+    /// Replaces the old flow where JS ran its own broad-phase and uploaded
+    /// `neighbors`/`neighbor_offsets`/`neighbor_counts` every frame: now JS
+    /// only needs to upload positions/radii/states/layers and call this
+    /// once before `compute_forces`. `cell_size` should be at least
+    /// `BoidsParams::max_interaction_radius` (use `suggested_cell_size` to
+    /// read that value straight off the configured params).
+    #[wasm_bindgen]
+    pub fn build_neighbors(&mut self, cell_size: f32) {
+        self.use_cluster_kernel = false;
+        self.neighbors.build_from_grid(&self.buffer, cell_size);
+    }
+
+    /// Build a half neighbor list instead: each interacting pair is stored
+    /// once (in the lower-indexed unit's list) rather than twice.
+    ///
+    /// `compute_forces` detects this automatically and switches to the
+    /// Newton's-third-law force kernel that reuses one evaluation for both
+    /// units in a pair. That kernel can't run on the SIMD path (it writes
+    /// into both units' force slots per iteration rather than accumulating
+    /// in local registers), so calling this opts a frame out of WASM SIMD in
+    /// exchange for roughly half the pairwise math.
+    #[wasm_bindgen]
+    pub fn build_neighbors_half(&mut self, cell_size: f32) {
+        self.use_cluster_kernel = false;
+        self.neighbors.build_from_grid_half(&self.buffer, cell_size);
+    }
+
+    /// Build a cluster-to-cluster neighbor list instead of the default
+    /// unit-to-unit one.
+    ///
+    /// `compute_forces` detects this and switches to the cluster-based SIMD
+    /// kernel, which loads each neighbor cluster with a single aligned load
+    /// instead of gathering 4 scattered neighbor indices per batch (see
+    /// `simd::compute_all_forces_simd_clustered`). Only available on the
+    /// `wasm32` SIMD path; on a native build `compute_forces` ignores it
+    /// and runs the native wide-SIMD kernel (or the scalar half-list
+    /// kernel) over whichever unit-to-unit list
+    /// `build_neighbors`/`build_neighbors_half` last populated (empty if
+    /// neither was called).
+    #[wasm_bindgen]
+    pub fn build_neighbors_clustered(&mut self, cell_size: f32) {
+        self.use_cluster_kernel = true;
+        self.cluster_neighbors.build_from_grid(&self.buffer, cell_size);
+    }
+
+    /// Get the recommended `build_neighbors` cell size for the currently
+    /// configured separation/cohesion/alignment radii
+    #[wasm_bindgen]
+    pub fn suggested_cell_size(&self) -> f32 {
+        self.params.max_interaction_radius()
     }
 
     // ==================== Parameters ====================
@@ -244,20 +443,224 @@ impl BoidsEngine {
         self.params.min_moving_speed = speed;
     }
 
+    /// Enable or disable Gay-Berne-style anisotropic separation: elongated
+    /// units (populate `orientation_x/y_ptr` and `aspect_ratio_ptr`) get an
+    /// orientation-dependent contact distance instead of
+    /// `combined_r * separation_radius`. Off by default, so formations of
+    /// round units (the common case) pay none of the extra per-pair cost.
+    /// Honored by the per-unit `wasm32` SIMD kernel and the half-list
+    /// kernel; the clustered kernel (`build_neighbors_clustered`) and the
+    /// native wide-SIMD kernel that full lists use on native builds always
+    /// use the isotropic distance.
+    #[wasm_bindgen]
+    pub fn set_anisotropic_separation(&mut self, enabled: bool) {
+        self.params.anisotropic_separation = enabled;
+    }
+
+    /// Set the world bounds units are steered to stay inside of
+    ///
+    /// `mode` selects containment behavior: 0 = `SteerAway` (accumulate an
+    /// inward force, read from `force_bound_x/y_ptr`), 1 = `Wrap` (teleport
+    /// to the opposite edge), 2 = `Bounce` (reflect velocity off the edge).
+    /// `margin`/`turn_strength` only affect `SteerAway`. Unrecognized `mode`
+    /// values fall back to `SteerAway`.
+    ///
+    /// This is the default bounds applied to any unit whose layer has no
+    /// override set via `set_layer_bounds`.
+    #[wasm_bindgen]
+    pub fn set_world_bounds(
+        &mut self,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+        margin: f32,
+        turn_strength: f32,
+        mode: u8,
+    ) {
+        self.params.world_bounds = Some(WorldBounds {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            margin,
+            turn_strength,
+            mode: BoundaryMode::from_u8(mode),
+        });
+    }
+
+    /// Disable world-bounds containment steering
+    #[wasm_bindgen]
+    pub fn clear_world_bounds(&mut self) {
+        self.params.world_bounds = None;
+    }
+
+    /// Override `world_bounds` for a single layer (e.g. a taller rect for a
+    /// flying layer). Only honored by `scalar::compute_boundary_forces`; on
+    /// `wasm32` with SIMD available, `compute_forces`/`step` instead use
+    /// `compute_boundary_forces_simd`, which applies `world_bounds`
+    /// uniformly regardless of layer. See `BoidsParams::layer_bounds` docs.
+    #[wasm_bindgen]
+    pub fn set_layer_bounds(
+        &mut self,
+        layer: u8,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+        margin: f32,
+        turn_strength: f32,
+        mode: u8,
+    ) {
+        let bounds = WorldBounds {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            margin,
+            turn_strength,
+            mode: BoundaryMode::from_u8(mode),
+        };
+        match self
+            .params
+            .layer_bounds
+            .iter_mut()
+            .find(|(l, _)| *l == layer)
+        {
+            Some((_, existing)) => *existing = bounds,
+            None => self.params.layer_bounds.push((layer, bounds)),
+        }
+    }
+
+    /// Remove a layer's `world_bounds` override, if any
+    #[wasm_bindgen]
+    pub fn clear_layer_bounds(&mut self, layer: u8) {
+        self.params.layer_bounds.retain(|(l, _)| *l != layer);
+    }
+
+    /// Set the speed range a unit's velocity is clamped to after integration
+    #[wasm_bindgen]
+    pub fn set_max_speed(&mut self, max_speed: f32, min_speed: f32) {
+        self.params.max_speed = max_speed;
+        self.params.min_speed = min_speed;
+    }
+
+    /// Set the maximum magnitude of the combined steering acceleration
+    /// (separation + cohesion + alignment + boundary/obstacle + seek) used by `step`
+    #[wasm_bindgen]
+    pub fn set_max_force(&mut self, max_force: f32) {
+        self.params.max_force = max_force;
+    }
+
     // ==================== Computation ====================
 
-    /// Compute all boids forces using SIMD
+    /// Compute all boids forces, using SIMD where available
     ///
     /// Prerequisites:
     /// 1. Populate input arrays (positions, velocities, radii, states, layers)
     /// 2. Set unit_count
-    /// 3. Populate neighbor arrays (neighbors, offsets, counts)
-    /// 4. Set neighbor_total
+    /// 3. Call `build_neighbors`
     ///
     /// After calling, read results from force arrays.
+    ///
+    /// If neighbors were built with `build_neighbors_half`, this always runs
+    /// the half-list scalar kernel (it needs to write into both units of a
+    /// pair per iteration, which the SIMD path's register-local accumulators
+    /// can't do). Otherwise, on `wasm32` this dispatches to the f32x4 SIMD
+    /// kernel; on `x86_64` it runs the native wide-SIMD kernel at whichever
+    /// of AVX-512/AVX2/SSE2 the CPU actually supports (see
+    /// `native::compute_all_forces_native_dispatch`); other native targets
+    /// use the compile-time lane width (see `native::NativeLaneF32`) --
+    /// either way headless/server-side builds get SIMD throughput too
+    /// instead of falling all the way back to `scalar`.
     #[wasm_bindgen]
     pub fn compute_forces(&mut self) {
-        simd::compute_all_forces_simd(&mut self.buffer, &self.neighbors, &self.params);
+        if self.neighbors.is_half() {
+            scalar::compute_all_forces_half(&mut self.buffer, &self.neighbors, &self.params);
+            scalar::compute_boundary_forces(&mut self.buffer, &self.obstacles, &self.params);
+            self.compute_seek();
+            return;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if simd::simd_available() {
+                if self.use_cluster_kernel {
+                    simd::compute_all_forces_simd_clustered(&mut self.buffer, &self.cluster_neighbors, &self.params);
+                } else {
+                    simd::compute_all_forces_simd(&mut self.buffer, &self.neighbors, &self.params);
+                }
+                simd::compute_boundary_forces_simd(&mut self.buffer, &self.obstacles, &self.params);
+                self.compute_seek();
+                return;
+            }
+        }
+
+        #[cfg(all(not(target_arch = "wasm32"), target_arch = "x86_64"))]
+        {
+            native::compute_all_forces_native_dispatch(&mut self.buffer, &self.neighbors, &self.params);
+            scalar::compute_boundary_forces(&mut self.buffer, &self.obstacles, &self.params);
+            self.compute_seek();
+        }
+
+        #[cfg(all(not(target_arch = "wasm32"), not(target_arch = "x86_64")))]
+        {
+            native::compute_all_forces_native::<native::NativeLaneF32>(&mut self.buffer, &self.neighbors, &self.params);
+            scalar::compute_boundary_forces(&mut self.buffer, &self.obstacles, &self.params);
+            self.compute_seek();
+        }
+    }
+
+    /// Compute flow-field goal-seeking force, if a flow field is set; a
+    /// separate step from `compute_boundary_forces[_simd]` because grid
+    /// sampling is scattered per-unit and gains nothing from SIMD batching
+    /// (see `scalar::compute_seek_force`), so there's no SIMD variant to
+    /// dispatch to on `wasm32`.
+    fn compute_seek(&mut self) {
+        if let Some(flow_field) = self.flow_field.as_ref() {
+            scalar::compute_seek_force(&mut self.buffer, flow_field, &self.params);
+        }
+    }
+
+    /// Run one full simulation tick: compute forces, then blend them into an
+    /// acceleration, integrate velocity/position, and write the results
+    /// in-place over `positions_x/y` and `velocities_x/y`.
+    ///
+    /// This is the all-in-one entry point JS should call once neighbors are
+    /// built: upload state, call `build_neighbors`/`step`, read back
+    /// positions, with no other JS-side passes over the unit arrays.
+    #[wasm_bindgen]
+    pub fn step(&mut self, dt: f32) {
+        self.compute_forces();
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if simd::simd_available() {
+                simd::integrate_simd(&mut self.buffer, &self.params, dt);
+                return;
+            }
+        }
+
+        scalar::integrate(&mut self.buffer, &self.params, dt);
+    }
+
+    /// Run one JS-visible tick as `substeps` (clamped to at least 1) fixed
+    /// `dt / substeps`-sized passes of `step`, instead of a single `dt`-sized
+    /// one.
+    ///
+    /// Each substep recomputes forces against the same neighbor list and
+    /// re-integrates, so tightly packed units see each other's separation
+    /// force partway through the tick rather than only at its end -- this
+    /// bounds how far overlapping units can tunnel past each other before
+    /// the next `build_neighbors` call, without changing how often JS needs
+    /// to rebuild the spatial hash.
+    #[wasm_bindgen]
+    pub fn step_substeps(&mut self, dt: f32, substeps: u32) {
+        let substeps = substeps.max(1);
+        let sub_dt = dt / substeps as f32;
+        for _ in 0..substeps {
+            self.step(sub_dt);
+        }
     }
 
     /// Clear all buffers for reuse
@@ -265,6 +668,8 @@ impl BoidsEngine {
     pub fn clear(&mut self) {
         self.buffer.clear();
         self.neighbors.clear();
+        self.cluster_neighbors.clear();
+        self.use_cluster_kernel = false;
     }
 }
 
@@ -272,30 +677,35 @@ impl BoidsEngine {
 // Exposed as getter functions for JS (wasm_bindgen doesn't support const exports)
 
 /// Unit is active and should be processed
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub fn state_active() -> u8 {
     0
 }
 
 /// Unit is dead/inactive
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub fn state_dead() -> u8 {
     1
 }
 
 /// Unit is flying (different collision layer)
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub fn state_flying() -> u8 {
     2
 }
 
 /// Unit is gathering resources (no separation)
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub fn state_gathering() -> u8 {
     3
 }
 
 /// Unit is a worker (special rules)
+#[cfg(not(all(target_arch = "wasm32", target_os = "wasi")))]
 #[wasm_bindgen]
 pub fn state_worker() -> u8 {
     4
@@ -303,7 +713,7 @@ pub fn state_worker() -> u8 {
 
 // Note: wasm-bindgen automatically exports `memory` - don't define it manually
 
-#[cfg(test)]
+#[cfg(all(test, not(all(target_arch = "wasm32", target_os = "wasi"))))]
 mod tests {
     use super::*;
 
@@ -320,4 +730,19 @@ mod tests {
         #[cfg(not(target_arch = "wasm32"))]
         assert!(!simd_supported());
     }
+
+    #[test]
+    fn test_step_substeps_advances_like_repeated_single_steps() {
+        let mut engine = BoidsEngine::new(4);
+        unsafe {
+            *engine.buffer.velocities_x.add(0) = 1.0;
+            *engine.buffer.states.add(0) = state_active();
+        }
+        engine.buffer.set_count(1);
+
+        engine.step_substeps(1.0, 4);
+
+        let (px, _) = unsafe { engine.buffer.get_position(0) };
+        assert!(px > 0.0, "unit should have advanced in +x across the substeps");
+    }
 }
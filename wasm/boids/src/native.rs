@@ -0,0 +1,525 @@
+//! Portable wide-SIMD native backend
+//!
+//! `simd.rs` is wasm32-only (it's built on `std::arch::wasm32`'s `v128`
+//! intrinsics), so native builds fell back to the fully scalar path in
+//! `scalar.rs`. That's fine for portability but leaves 4-16x throughput on
+//! the table for a server or bot running headless simulation. This module
+//! mirrors `simd::vector_ops` generically over lane count using the `wide`
+//! crate (>= 1.5, pinned in `Cargo.toml`; which already wraps SSE/AVX/AVX-512
+//! behind one portable type per width), so one generic kernel can be
+//! instantiated at the WASM backend's width (4) or whichever native width the
+//! `lanes8`/`lanes16` feature selects. `SimdLane::lt`/`gt`/`select` below call
+//! `wide`'s inherent `simd_lt`/`simd_gt`/`select` methods, not the
+//! `CmpLt`/`CmpGt` traits of the same name, which `wide` deprecated in favor
+//! of the inherent methods.
+//!
+//! `NativeLaneF32` below picks a lane width at compile time via cargo
+//! feature, which is the only option on non-x86 targets. On `x86_64`,
+//! `compute_all_forces_native_dispatch` instead picks the width at runtime
+//! with `is_x86_feature_detected!` -- AVX-512 (16-wide) if present, else
+//! AVX2 (8-wide), else the SSE2 baseline (4-wide) -- so a single binary gets
+//! the best width for whatever CPU it actually runs on, the same way a
+//! multi-versioned MD kernel dispatches its inner loop per ISA rather than
+//! committing to one at build time.
+
+use wide::{f32x16, f32x4, f32x8};
+
+use crate::simd::BoidsParams;
+use crate::soa::{BoidsBuffer, NeighborList, UnitState};
+
+/// Lane width selected by cargo feature; defaults to 4 (matching the WASM
+/// backend) when neither wider feature is enabled.
+#[cfg(feature = "lanes16")]
+pub type NativeLaneF32 = f32x16;
+#[cfg(all(feature = "lanes8", not(feature = "lanes16")))]
+pub type NativeLaneF32 = f32x8;
+#[cfg(not(any(feature = "lanes8", feature = "lanes16")))]
+pub type NativeLaneF32 = f32x4;
+
+/// Lane operations the force kernel needs, implemented once per `wide`
+/// width. Mirrors `simd::vector_ops`'s WASM-intrinsic functions one for
+/// one, so `compute_unit_forces_native` is written against this trait
+/// instead of a specific width and can be instantiated at 4, 8, or 16 lanes.
+pub trait SimdLane: Copy {
+    /// Number of f32 lanes this width processes per instruction
+    const LANES: usize;
+
+    fn splat(v: f32) -> Self;
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+    fn div(self, rhs: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn max(self, rhs: Self) -> Self;
+    /// Comparison mask: all-bits-set lanes where `self < rhs`, else zero
+    fn lt(self, rhs: Self) -> Self;
+    /// Comparison mask: all-bits-set lanes where `self > rhs`, else zero
+    fn gt(self, rhs: Self) -> Self;
+    /// Bitwise AND, used to combine/apply masks (mirrors `vector_ops::apply_mask`)
+    fn and(self, rhs: Self) -> Self;
+    /// Select `a`'s lanes where `mask` is set, `b`'s otherwise (mirrors
+    /// `vector_ops::clamp_magnitude_4`'s `v128_bitselect` usage)
+    fn select(mask: Self, a: Self, b: Self) -> Self;
+    /// Sum every lane down to a single scalar
+    fn horizontal_sum(self) -> f32;
+    /// Gather `Self::LANES` scattered f32 values via scalar loads -- same
+    /// tradeoff `vector_ops::gather_f32x4` makes on WASM, just wider
+    ///
+    /// # Safety
+    /// Every index in `indices` (length `Self::LANES`) must be in bounds for `ptr`.
+    unsafe fn gather(ptr: *const f32, indices: &[usize]) -> Self;
+    /// Unpack lanes back into a plain array for the scalar tail/reductions
+    fn to_array(self) -> Vec<f32>;
+}
+
+macro_rules! impl_simd_lane {
+    ($ty:ty, $lanes:expr) => {
+        impl SimdLane for $ty {
+            const LANES: usize = $lanes;
+
+            #[inline]
+            fn splat(v: f32) -> Self {
+                <$ty>::splat(v)
+            }
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            #[inline]
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+            #[inline]
+            fn div(self, rhs: Self) -> Self {
+                self / rhs
+            }
+            #[inline]
+            fn sqrt(self) -> Self {
+                self.sqrt()
+            }
+            #[inline]
+            fn max(self, rhs: Self) -> Self {
+                self.max(rhs)
+            }
+            #[inline]
+            fn lt(self, rhs: Self) -> Self {
+                self.simd_lt(rhs)
+            }
+            #[inline]
+            fn gt(self, rhs: Self) -> Self {
+                self.simd_gt(rhs)
+            }
+            #[inline]
+            fn and(self, rhs: Self) -> Self {
+                self & rhs
+            }
+            #[inline]
+            fn select(mask: Self, a: Self, b: Self) -> Self {
+                mask.select(a, b)
+            }
+            #[inline]
+            fn horizontal_sum(self) -> f32 {
+                self.to_array().iter().sum()
+            }
+            #[inline]
+            unsafe fn gather(ptr: *const f32, indices: &[usize]) -> Self {
+                let mut lanes = [0.0f32; $lanes];
+                for (lane, &idx) in lanes.iter_mut().zip(indices.iter()) {
+                    *lane = *ptr.add(idx);
+                }
+                <$ty>::from(lanes)
+            }
+            #[inline]
+            fn to_array(self) -> Vec<f32> {
+                <$ty>::to_array(self).to_vec()
+            }
+        }
+    };
+}
+
+impl_simd_lane!(f32x4, 4);
+impl_simd_lane!(f32x8, 8);
+impl_simd_lane!(f32x16, 16);
+
+/// Check if a neighbor should be processed for boids forces -- identical
+/// rules to `simd::is_valid_neighbor`, duplicated here since that one is
+/// private to the wasm32-only module
+#[inline]
+unsafe fn is_valid_neighbor_native(
+    buffer: &BoidsBuffer,
+    unit_idx: usize,
+    unit_state: u8,
+    unit_layer: u8,
+    neighbor_idx: usize,
+) -> bool {
+    if neighbor_idx == unit_idx {
+        return false;
+    }
+
+    let neighbor_state = *buffer.states.add(neighbor_idx);
+    if neighbor_state == UnitState::Dead as u8 {
+        return false;
+    }
+
+    let neighbor_layer = *buffer.layers.add(neighbor_idx);
+    if neighbor_layer != unit_layer {
+        return false;
+    }
+
+    if unit_state == UnitState::Worker as u8 && neighbor_state == UnitState::Worker as u8 {
+        return false;
+    }
+
+    if neighbor_state == UnitState::Gathering as u8 {
+        return false;
+    }
+
+    true
+}
+
+/// Compute all boids forces for all units using the native wide-SIMD
+/// kernel, at whichever lane width `L` is instantiated with
+///
+/// Same contract as `simd::compute_all_forces_simd`: forces are written
+/// directly to the buffer's force arrays, dead units are skipped entirely.
+pub fn compute_all_forces_native<L: SimdLane>(
+    buffer: &mut BoidsBuffer,
+    neighbors: &NeighborList,
+    params: &BoidsParams,
+) {
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
+
+    buffer.zero_forces();
+
+    for unit_idx in 0..count {
+        unsafe {
+            compute_unit_forces_native::<L>(buffer, neighbors, params, unit_idx);
+        }
+    }
+}
+
+/// Runtime-dispatched counterpart of `compute_all_forces_native`: picks the
+/// widest lane type the running CPU actually supports instead of whatever
+/// `NativeLaneF32` resolved to at compile time. Only available on `x86_64`,
+/// where `is_x86_feature_detected!` exists; other native targets should keep
+/// calling `compute_all_forces_native::<NativeLaneF32>` directly.
+#[cfg(target_arch = "x86_64")]
+pub fn compute_all_forces_native_dispatch(
+    buffer: &mut BoidsBuffer,
+    neighbors: &NeighborList,
+    params: &BoidsParams,
+) {
+    if is_x86_feature_detected!("avx512f") {
+        unsafe { run_avx512(buffer, neighbors, params) };
+    } else if is_x86_feature_detected!("avx2") {
+        unsafe { run_avx2(buffer, neighbors, params) };
+    } else {
+        // SSE2 is part of the x86_64 baseline, so this is always available.
+        compute_all_forces_native::<f32x4>(buffer, neighbors, params);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn run_avx512(buffer: &mut BoidsBuffer, neighbors: &NeighborList, params: &BoidsParams) {
+    compute_all_forces_native::<f32x16>(buffer, neighbors, params);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn run_avx2(buffer: &mut BoidsBuffer, neighbors: &NeighborList, params: &BoidsParams) {
+    compute_all_forces_native::<f32x8>(buffer, neighbors, params);
+}
+
+/// Compute forces for a single unit using `L`-wide SIMD over neighbor
+/// batches, the native-lane-width counterpart of `simd::compute_unit_forces_simd`
+unsafe fn compute_unit_forces_native<L: SimdLane>(
+    buffer: &mut BoidsBuffer,
+    neighbors: &NeighborList,
+    params: &BoidsParams,
+    unit_idx: usize,
+) {
+    let unit_state = *buffer.states.add(unit_idx);
+    if unit_state == UnitState::Dead as u8 {
+        return;
+    }
+
+    let ux = *buffer.positions_x.add(unit_idx);
+    let uy = *buffer.positions_y.add(unit_idx);
+    let ur = *buffer.radii.add(unit_idx);
+    let unit_layer = *buffer.layers.add(unit_idx);
+
+    let mut sep_x_acc = L::splat(0.0);
+    let mut sep_y_acc = L::splat(0.0);
+    let mut coh_x_acc = L::splat(0.0);
+    let mut coh_y_acc = L::splat(0.0);
+    let mut coh_count_acc = L::splat(0.0);
+    let mut align_vx_acc = L::splat(0.0);
+    let mut align_vy_acc = L::splat(0.0);
+    let mut align_count_acc = L::splat(0.0);
+
+    let ux_l = L::splat(ux);
+    let uy_l = L::splat(uy);
+    let ur_l = L::splat(ur);
+
+    let sep_radius = L::splat(params.separation_radius);
+    let sep_strength = L::splat(params.separation_strength);
+    let coh_radius_sq = L::splat(params.cohesion_radius * params.cohesion_radius);
+    let align_radius_sq = L::splat(params.alignment_radius * params.alignment_radius);
+    let min_speed_sq = L::splat(params.min_moving_speed * params.min_moving_speed);
+    let epsilon = L::splat(0.0001);
+    let one = L::splat(1.0);
+
+    let neighbor_slice = neighbors.get_neighbors(unit_idx);
+    let neighbor_count = neighbor_slice.len();
+    let lanes = L::LANES;
+    let simd_count = neighbor_count / lanes * lanes;
+
+    let mut indices = vec![0usize; lanes];
+    for batch_start in (0..simd_count).step_by(lanes) {
+        for (lane, slot) in indices.iter_mut().enumerate() {
+            *slot = neighbor_slice[batch_start + lane] as usize;
+        }
+
+        let mut mask_bits = vec![0.0f32; lanes];
+        for (lane, &ni) in indices.iter().enumerate() {
+            if is_valid_neighbor_native(buffer, unit_idx, unit_state, unit_layer, ni) {
+                mask_bits[lane] = f32::from_bits(u32::MAX);
+            }
+        }
+        let valid_mask = array_to_lane::<L>(&mask_bits);
+
+        let nx = L::gather(buffer.positions_x, &indices);
+        let ny = L::gather(buffer.positions_y, &indices);
+        let nr = L::gather(buffer.radii, &indices);
+
+        let dx = ux_l.sub(nx);
+        let dy = uy_l.sub(ny);
+        let dist_sq = dx.mul(dx).add(dy.mul(dy));
+
+        // === SEPARATION ===
+        let combined_r = ur_l.add(nr);
+        let sep_dist = combined_r.mul(sep_radius);
+        let sep_dist_sq = sep_dist.mul(sep_dist);
+
+        let in_sep_range = dist_sq.lt(sep_dist_sq).and(dist_sq.gt(epsilon));
+        let sep_mask = valid_mask.and(in_sep_range);
+
+        let dist = dist_sq.max(epsilon).sqrt();
+        let inv_dist = one.div(dist);
+        let strength = sep_strength.mul(one.sub(dist.div(sep_dist)));
+
+        let sep_fx = dx.mul(inv_dist).mul(strength);
+        let sep_fy = dy.mul(inv_dist).mul(strength);
+
+        sep_x_acc = sep_x_acc.add(sep_fx.and(sep_mask));
+        sep_y_acc = sep_y_acc.add(sep_fy.and(sep_mask));
+
+        // === COHESION ===
+        let in_coh_range = dist_sq.lt(coh_radius_sq);
+        let coh_mask = valid_mask.and(in_coh_range);
+
+        coh_x_acc = coh_x_acc.add(nx.and(coh_mask));
+        coh_y_acc = coh_y_acc.add(ny.and(coh_mask));
+        coh_count_acc = coh_count_acc.add(one.and(coh_mask));
+
+        // === ALIGNMENT ===
+        let nvx = L::gather(buffer.velocities_x, &indices);
+        let nvy = L::gather(buffer.velocities_y, &indices);
+        let speed_sq = nvx.mul(nvx).add(nvy.mul(nvy));
+
+        let in_align_range = dist_sq.lt(align_radius_sq);
+        let is_moving = speed_sq.gt(min_speed_sq);
+        let align_mask = valid_mask.and(in_align_range).and(is_moving);
+
+        let speed = speed_sq.max(epsilon).sqrt();
+        let inv_speed = one.div(speed);
+        let norm_vx = nvx.mul(inv_speed);
+        let norm_vy = nvy.mul(inv_speed);
+
+        align_vx_acc = align_vx_acc.add(norm_vx.and(align_mask));
+        align_vy_acc = align_vy_acc.add(norm_vy.and(align_mask));
+        align_count_acc = align_count_acc.add(one.and(align_mask));
+    }
+
+    let mut sep_x = sep_x_acc.horizontal_sum();
+    let mut sep_y = sep_y_acc.horizontal_sum();
+    let mut coh_sum_x = coh_x_acc.horizontal_sum();
+    let mut coh_sum_y = coh_y_acc.horizontal_sum();
+    let mut coh_count = coh_count_acc.horizontal_sum();
+    let mut align_sum_vx = align_vx_acc.horizontal_sum();
+    let mut align_sum_vy = align_vy_acc.horizontal_sum();
+    let mut align_count = align_count_acc.horizontal_sum();
+
+    // Scalar tail: process remaining neighbors (count % lanes)
+    for i in simd_count..neighbor_count {
+        let ni = neighbor_slice[i] as usize;
+        if !is_valid_neighbor_native(buffer, unit_idx, unit_state, unit_layer, ni) {
+            continue;
+        }
+
+        let nx = *buffer.positions_x.add(ni);
+        let ny = *buffer.positions_y.add(ni);
+        let nr = *buffer.radii.add(ni);
+
+        let dx = ux - nx;
+        let dy = uy - ny;
+        let dist_sq = dx * dx + dy * dy;
+
+        let combined_r = ur + nr;
+        let sep_dist = combined_r * params.separation_radius;
+        let sep_dist_sq = sep_dist * sep_dist;
+
+        if dist_sq < sep_dist_sq && dist_sq > 0.0001 {
+            let dist = dist_sq.sqrt();
+            let strength = params.separation_strength * (1.0 - dist / sep_dist);
+            sep_x += (dx / dist) * strength;
+            sep_y += (dy / dist) * strength;
+        }
+
+        if dist_sq < params.cohesion_radius * params.cohesion_radius {
+            coh_sum_x += nx;
+            coh_sum_y += ny;
+            coh_count += 1.0;
+        }
+
+        if dist_sq < params.alignment_radius * params.alignment_radius {
+            let nvx = *buffer.velocities_x.add(ni);
+            let nvy = *buffer.velocities_y.add(ni);
+            let speed_sq = nvx * nvx + nvy * nvy;
+
+            if speed_sq > params.min_moving_speed * params.min_moving_speed {
+                let speed = speed_sq.sqrt();
+                align_sum_vx += nvx / speed;
+                align_sum_vy += nvy / speed;
+                align_count += 1.0;
+            }
+        }
+    }
+
+    let sep_mag_sq = sep_x * sep_x + sep_y * sep_y;
+    if sep_mag_sq > params.max_separation_force * params.max_separation_force {
+        let scale = params.max_separation_force / sep_mag_sq.sqrt();
+        sep_x *= scale;
+        sep_y *= scale;
+    }
+
+    *buffer.force_sep_x.add(unit_idx) = sep_x;
+    *buffer.force_sep_y.add(unit_idx) = sep_y;
+
+    if coh_count > 0.0 {
+        let center_x = coh_sum_x / coh_count;
+        let center_y = coh_sum_y / coh_count;
+        let to_center_x = center_x - ux;
+        let to_center_y = center_y - uy;
+        let dist = (to_center_x * to_center_x + to_center_y * to_center_y).sqrt();
+
+        if dist > 0.1 {
+            *buffer.force_coh_x.add(unit_idx) = (to_center_x / dist) * params.cohesion_strength;
+            *buffer.force_coh_y.add(unit_idx) = (to_center_y / dist) * params.cohesion_strength;
+        }
+    }
+
+    if align_count > 0.0 {
+        let avg_vx = align_sum_vx / align_count;
+        let avg_vy = align_sum_vy / align_count;
+        let mag = (avg_vx * avg_vx + avg_vy * avg_vy).sqrt();
+
+        if mag > 0.1 {
+            *buffer.force_align_x.add(unit_idx) = (avg_vx / mag) * params.alignment_strength;
+            *buffer.force_align_y.add(unit_idx) = (avg_vy / mag) * params.alignment_strength;
+        }
+    }
+}
+
+/// Build a lane value directly from a plain array (used for the validity
+/// mask, which is assembled scalar-side since it depends on several branchy
+/// per-lane state/layer checks rather than arithmetic)
+#[inline]
+fn array_to_lane<L: SimdLane>(values: &[f32]) -> L {
+    debug_assert_eq!(values.len(), L::LANES);
+    // SAFETY: `values` has exactly `L::LANES` entries and every index here
+    // is `< values.len()`, so the gather is in-bounds.
+    unsafe { L::gather(values.as_ptr(), &(0..L::LANES).collect::<Vec<_>>()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soa::UnitState;
+
+    #[test]
+    fn test_native_matches_scalar_separation() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+        }
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let params = BoidsParams::default();
+        compute_all_forces_native::<f32x4>(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            let (sep_x, _) = buffer.get_separation_force(0);
+            // Same golden vector as scalar::test_golden_vector_matches_simd_separation
+            assert!((sep_x + 0.75).abs() < 1e-4, "sep_x = {sep_x}");
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_native_dispatch_matches_scalar_separation() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+        }
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let params = BoidsParams::default();
+        compute_all_forces_native_dispatch(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            let (sep_x, _) = buffer.get_separation_force(0);
+            // Whatever width this CPU's runtime dispatch lands on, the
+            // result must still match the golden isotropic-separation vector.
+            assert!((sep_x + 0.75).abs() < 1e-4, "sep_x = {sep_x}");
+        }
+    }
+}
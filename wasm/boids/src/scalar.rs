@@ -0,0 +1,1342 @@
+//! Scalar Reference Boids Force Calculations
+//!
+//! Portable, non-SIMD implementation of the same separation/cohesion/
+//! alignment math as `simd::compute_all_forces_simd`. Native targets run
+//! `native`'s wide-SIMD kernel for full neighbor lists; this module serves
+//! the half-list path and the boundary forces there, and doubles as the
+//! golden reference the SIMD kernels are checked against.
+//!
+//! Keeping this in its own module (mirroring the SIMD kernel's structure)
+//! lets the force logic be unit-tested on the host and gives consumers a
+//! portable native build of the engine for headless simulation and replay.
+
+use crate::soa::{BoidsBuffer, FlowField, NeighborList, ObstacleList, UnitState};
+use crate::simd::{BoidsParams, BoundaryMode};
+
+/// Compute all boids forces for all units using the scalar reference path
+///
+/// Same contract as `simd::compute_all_forces_simd`: forces are written
+/// directly to the buffer's force arrays, dead units are skipped entirely.
+pub fn compute_all_forces(buffer: &mut BoidsBuffer, neighbors: &NeighborList, params: &BoidsParams) {
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
+
+    buffer.zero_forces();
+
+    for i in 0..count {
+        compute_unit_forces_scalar(buffer, neighbors, params, i);
+    }
+}
+
+/// Compute forces for a single unit by scanning its neighbor list directly
+fn compute_unit_forces_scalar(
+    buffer: &mut BoidsBuffer,
+    neighbors: &NeighborList,
+    params: &BoidsParams,
+    unit_idx: usize,
+) {
+    unsafe {
+        let unit_state = *buffer.states.add(unit_idx);
+
+        // Skip dead/inactive units
+        if unit_state == UnitState::Dead as u8 {
+            return;
+        }
+
+        let unit_layer = *buffer.layers.add(unit_idx);
+        let ux = *buffer.positions_x.add(unit_idx);
+        let uy = *buffer.positions_y.add(unit_idx);
+        let ur = *buffer.radii.add(unit_idx);
+        let u_orient_x = *buffer.orientation_x.add(unit_idx);
+        let u_orient_y = *buffer.orientation_y.add(unit_idx);
+        let u_kappa = *buffer.aspect_ratio.add(unit_idx);
+
+        let mut sep_x = 0.0f32;
+        let mut sep_y = 0.0f32;
+        let mut coh_sum_x = 0.0f32;
+        let mut coh_sum_y = 0.0f32;
+        let mut coh_count = 0.0f32;
+        let mut align_sum_vx = 0.0f32;
+        let mut align_sum_vy = 0.0f32;
+        let mut align_count = 0.0f32;
+
+        for &neighbor_idx in neighbors.get_neighbors(unit_idx) {
+            let ni = neighbor_idx as usize;
+
+            if ni == unit_idx {
+                continue;
+            }
+
+            let neighbor_state = *buffer.states.add(ni);
+            if neighbor_state == UnitState::Dead as u8 {
+                continue;
+            }
+
+            let neighbor_layer = *buffer.layers.add(ni);
+            if neighbor_layer != unit_layer {
+                continue;
+            }
+
+            // Skip worker-worker separation (allows clumping at minerals)
+            if unit_state == UnitState::Worker as u8 && neighbor_state == UnitState::Worker as u8 {
+                continue;
+            }
+
+            // Skip gathering units for separation
+            if neighbor_state == UnitState::Gathering as u8 {
+                continue;
+            }
+
+            let nx = *buffer.positions_x.add(ni);
+            let ny = *buffer.positions_y.add(ni);
+            let nr = *buffer.radii.add(ni);
+
+            let dx = ux - nx;
+            let dy = uy - ny;
+            let dist_sq = dx * dx + dy * dy;
+
+            // Separation: isotropic by default, or a Gay-Berne-style
+            // orientation-dependent contact distance when
+            // `anisotropic_separation` is set (see `simd::anisotropic_sigma`)
+            let combined_r = ur + nr;
+            let sigma0 = combined_r * params.separation_radius;
+            let sep_dist = if params.anisotropic_separation && dist_sq > 0.0001 {
+                let dist = dist_sq.sqrt();
+                let rx = dx / dist;
+                let ry = dy / dist;
+                let n_orient_x = *buffer.orientation_x.add(ni);
+                let n_orient_y = *buffer.orientation_y.add(ni);
+                let n_kappa = *buffer.aspect_ratio.add(ni);
+                crate::simd::anisotropic_sigma(
+                    sigma0, rx, ry, u_orient_x, u_orient_y, n_orient_x, n_orient_y, u_kappa, n_kappa,
+                )
+            } else {
+                sigma0
+            };
+            let sep_dist_sq = sep_dist * sep_dist;
+
+            if dist_sq < sep_dist_sq && dist_sq > 0.0001 {
+                let dist = dist_sq.sqrt();
+                let strength = params.separation_strength * (1.0 - dist / sep_dist);
+                sep_x += (dx / dist) * strength;
+                sep_y += (dy / dist) * strength;
+            }
+
+            // Cohesion
+            if dist_sq < params.cohesion_radius * params.cohesion_radius {
+                coh_sum_x += nx;
+                coh_sum_y += ny;
+                coh_count += 1.0;
+            }
+
+            // Alignment
+            if dist_sq < params.alignment_radius * params.alignment_radius {
+                let nvx = *buffer.velocities_x.add(ni);
+                let nvy = *buffer.velocities_y.add(ni);
+                let speed_sq = nvx * nvx + nvy * nvy;
+
+                if speed_sq > params.min_moving_speed * params.min_moving_speed {
+                    let speed = speed_sq.sqrt();
+                    align_sum_vx += nvx / speed;
+                    align_sum_vy += nvy / speed;
+                    align_count += 1.0;
+                }
+            }
+        }
+
+        // Clamp separation force magnitude
+        let sep_mag_sq = sep_x * sep_x + sep_y * sep_y;
+        if sep_mag_sq > params.max_separation_force * params.max_separation_force {
+            let scale = params.max_separation_force / sep_mag_sq.sqrt();
+            sep_x *= scale;
+            sep_y *= scale;
+        }
+
+        *buffer.force_sep_x.add(unit_idx) = sep_x;
+        *buffer.force_sep_y.add(unit_idx) = sep_y;
+
+        // Cohesion: direction toward center of mass
+        if coh_count > 0.0 {
+            let center_x = coh_sum_x / coh_count;
+            let center_y = coh_sum_y / coh_count;
+            let to_center_x = center_x - ux;
+            let to_center_y = center_y - uy;
+            let dist = (to_center_x * to_center_x + to_center_y * to_center_y).sqrt();
+
+            if dist > 0.1 {
+                *buffer.force_coh_x.add(unit_idx) = (to_center_x / dist) * params.cohesion_strength;
+                *buffer.force_coh_y.add(unit_idx) = (to_center_y / dist) * params.cohesion_strength;
+            }
+        }
+
+        // Alignment: direction toward average heading
+        if align_count > 0.0 {
+            let avg_vx = align_sum_vx / align_count;
+            let avg_vy = align_sum_vy / align_count;
+            let mag = (avg_vx * avg_vx + avg_vy * avg_vy).sqrt();
+
+            if mag > 0.1 {
+                *buffer.force_align_x.add(unit_idx) = (avg_vx / mag) * params.alignment_strength;
+                *buffer.force_align_y.add(unit_idx) = (avg_vy / mag) * params.alignment_strength;
+            }
+        }
+    }
+}
+
+/// Running center-of-mass/heading sums for cohesion and alignment, keyed by
+/// unit index. Separation is antisymmetric so it can be written straight to
+/// `force_sep_*` as each pair is visited, but cohesion/alignment are
+/// symmetric accumulations shared between both units in a pair -- they need
+/// a scratch sum+count per unit that survives until every pair touching that
+/// unit has been visited, then get normalized in a final pass.
+struct PairAccumulators {
+    coh_sum_x: Vec<f32>,
+    coh_sum_y: Vec<f32>,
+    coh_count: Vec<f32>,
+    align_sum_vx: Vec<f32>,
+    align_sum_vy: Vec<f32>,
+    align_count: Vec<f32>,
+}
+
+impl PairAccumulators {
+    fn zeroed(capacity: usize) -> Self {
+        Self {
+            coh_sum_x: vec![0.0; capacity],
+            coh_sum_y: vec![0.0; capacity],
+            coh_count: vec![0.0; capacity],
+            align_sum_vx: vec![0.0; capacity],
+            align_sum_vy: vec![0.0; capacity],
+            align_count: vec![0.0; capacity],
+        }
+    }
+}
+
+/// Compute all boids forces using a half neighbor list (`i < j` only, see
+/// `NeighborList::build_from_grid_half`), evaluating each interacting pair
+/// once and reusing the result for both units via Newton's third law.
+///
+/// Separation is antisymmetric: the pushaway vector is computed once from
+/// i -> j and applied as `+f` to i's force and `-f` to j's. Cohesion and
+/// alignment are symmetric, so each unit's contribution to the other's
+/// running sum is accumulated into `PairAccumulators` rather than a local
+/// register, since a single pass now touches both units of a pair instead of
+/// just one; the center-of-mass/heading normalization runs as a separate
+/// pass afterward, once every pair has been visited.
+///
+/// Skip rules (dead, cross-layer, worker-worker, gathering) are evaluated
+/// once per pair rather than once per unit, since a half list only ever
+/// presents each pair a single time.
+pub fn compute_all_forces_half(buffer: &mut BoidsBuffer, neighbors: &NeighborList, params: &BoidsParams) {
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
+
+    buffer.zero_forces();
+    let mut acc = PairAccumulators::zeroed(buffer.capacity());
+
+    unsafe {
+        for i in 0..count {
+            let i_state = *buffer.states.add(i);
+            if i_state == UnitState::Dead as u8 {
+                continue;
+            }
+
+            let i_layer = *buffer.layers.add(i);
+            let ix = *buffer.positions_x.add(i);
+            let iy = *buffer.positions_y.add(i);
+            let ir = *buffer.radii.add(i);
+            let i_orient_x = *buffer.orientation_x.add(i);
+            let i_orient_y = *buffer.orientation_y.add(i);
+            let i_kappa = *buffer.aspect_ratio.add(i);
+
+            for &neighbor_idx in neighbors.get_neighbors(i) {
+                let j = neighbor_idx as usize;
+
+                let j_state = *buffer.states.add(j);
+                if j_state == UnitState::Dead as u8 {
+                    continue;
+                }
+
+                let j_layer = *buffer.layers.add(j);
+                if j_layer != i_layer {
+                    continue;
+                }
+
+                let jx = *buffer.positions_x.add(j);
+                let jy = *buffer.positions_y.add(j);
+                let jr = *buffer.radii.add(j);
+
+                let dx = ix - jx;
+                let dy = iy - jy;
+                let dist_sq = dx * dx + dy * dy;
+
+                // Skip worker-worker and gathering pairs entirely -- for all
+                // three forces, not just separation. The full-list path only
+                // checks the neighbor's state since it visits each unit as
+                // "self" in turn (and its `continue` drops separation,
+                // cohesion, and alignment together); a half list has to
+                // account for both sides of the pair at once here to match.
+                let valid = !(i_state == UnitState::Worker as u8 && j_state == UnitState::Worker as u8)
+                    && i_state != UnitState::Gathering as u8
+                    && j_state != UnitState::Gathering as u8;
+
+                if valid {
+                    let combined_r = ir + jr;
+                    let sigma0 = combined_r * params.separation_radius;
+                    let sep_dist = if params.anisotropic_separation && dist_sq > 0.0001 {
+                        let dir_dist = dist_sq.sqrt();
+                        let rx = dx / dir_dist;
+                        let ry = dy / dir_dist;
+                        let j_orient_x = *buffer.orientation_x.add(j);
+                        let j_orient_y = *buffer.orientation_y.add(j);
+                        let j_kappa = *buffer.aspect_ratio.add(j);
+                        crate::simd::anisotropic_sigma(
+                            sigma0, rx, ry, i_orient_x, i_orient_y, j_orient_x, j_orient_y, i_kappa, j_kappa,
+                        )
+                    } else {
+                        sigma0
+                    };
+                    let sep_dist_sq = sep_dist * sep_dist;
+
+                    if dist_sq < sep_dist_sq && dist_sq > 0.0001 {
+                        let dist = dist_sq.sqrt();
+                        let strength = params.separation_strength * (1.0 - dist / sep_dist);
+                        let fx = (dx / dist) * strength;
+                        let fy = (dy / dist) * strength;
+
+                        *buffer.force_sep_x.add(i) += fx;
+                        *buffer.force_sep_y.add(i) += fy;
+                        *buffer.force_sep_x.add(j) -= fx;
+                        *buffer.force_sep_y.add(j) -= fy;
+                    }
+                }
+
+                if valid && dist_sq < params.cohesion_radius * params.cohesion_radius {
+                    acc.coh_sum_x[i] += jx;
+                    acc.coh_sum_y[i] += jy;
+                    acc.coh_count[i] += 1.0;
+                    acc.coh_sum_x[j] += ix;
+                    acc.coh_sum_y[j] += iy;
+                    acc.coh_count[j] += 1.0;
+                }
+
+                if valid && dist_sq < params.alignment_radius * params.alignment_radius {
+                    let jvx = *buffer.velocities_x.add(j);
+                    let jvy = *buffer.velocities_y.add(j);
+                    let j_speed_sq = jvx * jvx + jvy * jvy;
+                    if j_speed_sq > params.min_moving_speed * params.min_moving_speed {
+                        let speed = j_speed_sq.sqrt();
+                        acc.align_sum_vx[i] += jvx / speed;
+                        acc.align_sum_vy[i] += jvy / speed;
+                        acc.align_count[i] += 1.0;
+                    }
+
+                    let ivx = *buffer.velocities_x.add(i);
+                    let ivy = *buffer.velocities_y.add(i);
+                    let i_speed_sq = ivx * ivx + ivy * ivy;
+                    if i_speed_sq > params.min_moving_speed * params.min_moving_speed {
+                        let speed = i_speed_sq.sqrt();
+                        acc.align_sum_vx[j] += ivx / speed;
+                        acc.align_sum_vy[j] += ivy / speed;
+                        acc.align_count[j] += 1.0;
+                    }
+                }
+            }
+        }
+
+        for i in 0..count {
+            if *buffer.states.add(i) == UnitState::Dead as u8 {
+                continue;
+            }
+
+            let sep_x = *buffer.force_sep_x.add(i);
+            let sep_y = *buffer.force_sep_y.add(i);
+            let sep_mag_sq = sep_x * sep_x + sep_y * sep_y;
+            if sep_mag_sq > params.max_separation_force * params.max_separation_force {
+                let scale = params.max_separation_force / sep_mag_sq.sqrt();
+                *buffer.force_sep_x.add(i) = sep_x * scale;
+                *buffer.force_sep_y.add(i) = sep_y * scale;
+            }
+
+            if acc.coh_count[i] > 0.0 {
+                let ux = *buffer.positions_x.add(i);
+                let uy = *buffer.positions_y.add(i);
+                let center_x = acc.coh_sum_x[i] / acc.coh_count[i];
+                let center_y = acc.coh_sum_y[i] / acc.coh_count[i];
+                let to_center_x = center_x - ux;
+                let to_center_y = center_y - uy;
+                let dist = (to_center_x * to_center_x + to_center_y * to_center_y).sqrt();
+
+                if dist > 0.1 {
+                    *buffer.force_coh_x.add(i) = (to_center_x / dist) * params.cohesion_strength;
+                    *buffer.force_coh_y.add(i) = (to_center_y / dist) * params.cohesion_strength;
+                }
+            }
+
+            if acc.align_count[i] > 0.0 {
+                let avg_vx = acc.align_sum_vx[i] / acc.align_count[i];
+                let avg_vy = acc.align_sum_vy[i] / acc.align_count[i];
+                let mag = (avg_vx * avg_vx + avg_vy * avg_vy).sqrt();
+
+                if mag > 0.1 {
+                    *buffer.force_align_x.add(i) = (avg_vx / mag) * params.alignment_strength;
+                    *buffer.force_align_y.add(i) = (avg_vy / mag) * params.alignment_strength;
+                }
+            }
+        }
+    }
+}
+
+/// Scalar reference for world-bounds containment + obstacle avoidance,
+/// matching `simd::compute_boundary_forces_simd` unit for unit except that
+/// this one also honors per-layer `layer_bounds` overrides
+pub fn compute_boundary_forces(
+    buffer: &mut BoidsBuffer,
+    obstacles: &ObstacleList,
+    params: &BoidsParams,
+) {
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
+
+    unsafe {
+        std::ptr::write_bytes(buffer.force_bound_x, 0, buffer.capacity());
+        std::ptr::write_bytes(buffer.force_bound_y, 0, buffer.capacity());
+    }
+
+    unsafe {
+        // Unlike `compute_boundary_forces_simd`, this scalar kernel already
+        // walks units one at a time, so it can honor `layer_bounds`
+        // per-unit instead of only the global `world_bounds`.
+        for i in 0..count {
+            if *buffer.states.add(i) == UnitState::Dead as u8 {
+                continue;
+            }
+
+            let layer = *buffer.layers.add(i);
+            let Some(bounds) = params.bounds_for_layer(layer).copied() else {
+                continue;
+            };
+
+            match bounds.mode {
+                BoundaryMode::Wrap => crate::simd::apply_boundary_wrap_unit(buffer, &bounds, i),
+                BoundaryMode::Bounce => crate::simd::apply_boundary_bounce_unit(buffer, &bounds, i),
+                BoundaryMode::SteerAway => {
+                    let margin = bounds.margin.max(0.0001);
+                    let x = *buffer.positions_x.add(i);
+                    let y = *buffer.positions_y.add(i);
+
+                    let t_left = ((margin - (x - bounds.min_x)) / margin).clamp(0.0, 1.0);
+                    let t_right = ((margin - (bounds.max_x - x)) / margin).clamp(0.0, 1.0);
+                    let t_bottom = ((margin - (y - bounds.min_y)) / margin).clamp(0.0, 1.0);
+                    let t_top = ((margin - (bounds.max_y - y)) / margin).clamp(0.0, 1.0);
+
+                    *buffer.force_bound_x.add(i) += (t_left - t_right) * bounds.turn_strength;
+                    *buffer.force_bound_y.add(i) += (t_bottom - t_top) * bounds.turn_strength;
+                }
+            }
+        }
+
+        for obstacle_idx in 0..obstacles.len() {
+            let (ox, oy, oradius) = obstacles.get(obstacle_idx);
+
+            for i in 0..count {
+                if *buffer.states.add(i) == UnitState::Dead as u8 {
+                    continue;
+                }
+
+                let x = *buffer.positions_x.add(i);
+                let y = *buffer.positions_y.add(i);
+                let r = *buffer.radii.add(i);
+
+                let dx = x - ox;
+                let dy = y - oy;
+                let dist_sq = dx * dx + dy * dy;
+                let contact = r + oradius;
+                let contact_sq = contact * contact;
+
+                if dist_sq < contact_sq && dist_sq > 0.0001 {
+                    let dist = dist_sq.sqrt();
+                    let strength = params.max_separation_force * (1.0 - dist / contact);
+                    *buffer.force_bound_x.add(i) += (dx / dist) * strength;
+                    *buffer.force_bound_y.add(i) += (dy / dist) * strength;
+                }
+            }
+        }
+    }
+}
+
+/// Compute flow-field goal-seeking force for all units
+///
+/// Samples `flow_field` under each unit's current position and writes
+/// `params.seek_strength` times the sampled unit direction into
+/// `force_seek_x/y`. Grid sampling is a scattered, per-unit lookup with no
+/// useful SIMD batching (unlike the boundary/obstacle kernels, where the
+/// same rect or obstacle applies uniformly across a batch), so this is the
+/// only kernel -- scalar and SIMD builds both call it directly. Units whose
+/// position falls outside the flow field, or on an impassable/unreached
+/// cell, get zero seek force rather than a stale or garbage direction.
+pub fn compute_seek_force(buffer: &mut BoidsBuffer, flow_field: &FlowField, params: &BoidsParams) {
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
+
+    unsafe {
+        std::ptr::write_bytes(buffer.force_seek_x, 0, buffer.capacity());
+        std::ptr::write_bytes(buffer.force_seek_y, 0, buffer.capacity());
+
+        for i in 0..count {
+            if *buffer.states.add(i) == UnitState::Dead as u8 {
+                continue;
+            }
+
+            let x = *buffer.positions_x.add(i);
+            let y = *buffer.positions_y.add(i);
+
+            if let Some((dx, dy)) = flow_field.sample_direction(x, y) {
+                *buffer.force_seek_x.add(i) = dx * params.seek_strength;
+                *buffer.force_seek_y.add(i) = dy * params.seek_strength;
+            }
+        }
+    }
+}
+
+/// Scalar reference for `simd::integrate_simd`: blend the accumulated
+/// forces into an acceleration, integrate velocity and position with
+/// semi-implicit Euler, and write the results back in-place
+pub fn integrate(buffer: &mut BoidsBuffer, params: &BoidsParams, dt: f32) {
+    let count = buffer.len();
+    unsafe {
+        for i in 0..count {
+            crate::simd::integrate_unit_scalar(buffer, params, dt, i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar_separation() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            // Set up two units close together
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.layers.add(0) = 0;
+
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+            *buffer.layers.add(1) = 0;
+        }
+
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            // Unit 0 should be pushed left (negative x)
+            let (sep_x, sep_y) = buffer.get_separation_force(0);
+            assert!(sep_x < 0.0, "Unit 0 should be pushed left");
+            assert!(sep_y.abs() < 0.01, "No Y separation expected");
+
+            // Unit 1 should be pushed right (positive x)
+            let (sep_x, sep_y) = buffer.get_separation_force(1);
+            assert!(sep_x > 0.0, "Unit 1 should be pushed right");
+            assert!(sep_y.abs() < 0.01, "No Y separation expected");
+        }
+    }
+
+    #[test]
+    fn test_cohesion_force() {
+        let mut buffer = BoidsBuffer::new(8);
+        let mut neighbors = NeighborList::new(8);
+
+        unsafe {
+            // Unit 0 at origin
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.layers.add(0) = 0;
+
+            // Unit 1 at (5, 0) - within cohesion radius (8)
+            *buffer.positions_x.add(1) = 5.0;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+            *buffer.layers.add(1) = 0;
+        }
+
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            // Unit 0 should be pulled toward unit 1 (positive x direction)
+            let (coh_x, coh_y) = buffer.get_cohesion_force(0);
+            assert!(coh_x > 0.0, "Unit 0 should be pulled right toward unit 1");
+            assert!(coh_y.abs() < 0.01, "No Y cohesion expected");
+        }
+    }
+
+    #[test]
+    fn test_alignment_force() {
+        let mut buffer = BoidsBuffer::new(8);
+        let mut neighbors = NeighborList::new(8);
+
+        unsafe {
+            // Unit 0 at origin, stationary
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.velocities_x.add(0) = 0.0;
+            *buffer.velocities_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.layers.add(0) = 0;
+
+            // Unit 1 at (2, 0), moving in +Y direction
+            *buffer.positions_x.add(1) = 2.0;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.velocities_x.add(1) = 0.0;
+            *buffer.velocities_y.add(1) = 1.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+            *buffer.layers.add(1) = 0;
+        }
+
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            // Unit 0 should align with unit 1's velocity (positive y direction)
+            let (align_x, align_y) = buffer.get_alignment_force(0);
+            assert!(align_x.abs() < 0.01, "No X alignment expected");
+            assert!(align_y > 0.0, "Unit 0 should align toward +Y");
+        }
+    }
+
+    #[test]
+    fn test_skip_dead_units() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            // Unit 0 active
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.layers.add(0) = 0;
+
+            // Unit 1 dead (should be skipped)
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Dead as u8;
+            *buffer.layers.add(1) = 0;
+        }
+
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            // No forces should be applied since the only neighbor is dead
+            let (sep_x, sep_y) = buffer.get_separation_force(0);
+            assert_eq!(sep_x, 0.0, "No separation expected with dead neighbor");
+            assert_eq!(sep_y, 0.0, "No separation expected with dead neighbor");
+        }
+    }
+
+    #[test]
+    fn test_skip_different_layers() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            // Unit 0 on layer 0 (ground)
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.layers.add(0) = 0;
+
+            // Unit 1 on layer 1 (flying) - should be skipped
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+            *buffer.layers.add(1) = 1;
+        }
+
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            // No forces should be applied since neighbor is on different layer
+            let (sep_x, sep_y) = buffer.get_separation_force(0);
+            assert_eq!(sep_x, 0.0, "No separation expected across layers");
+            assert_eq!(sep_y, 0.0, "No separation expected across layers");
+        }
+    }
+
+    #[test]
+    fn test_many_neighbors() {
+        // Test with more than 4 neighbors to exercise the scalar loop over a
+        // neighbor count that isn't a multiple of the SIMD kernel's batch size
+        let mut buffer = BoidsBuffer::new(8);
+        let mut neighbors = NeighborList::new(8);
+
+        unsafe {
+            // Unit 0 at origin
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.layers.add(0) = 0;
+
+            // 6 neighbors surrounding unit 0
+            for i in 1..7 {
+                let angle = (i as f32) * std::f32::consts::PI / 3.0;
+                *buffer.positions_x.add(i) = 0.5 * angle.cos();
+                *buffer.positions_y.add(i) = 0.5 * angle.sin();
+                *buffer.radii.add(i) = 0.5;
+                *buffer.states.add(i) = UnitState::Active as u8;
+                *buffer.layers.add(i) = 0;
+            }
+        }
+
+        buffer.set_count(7);
+
+        neighbors.begin_unit(0);
+        for i in 1..7 {
+            neighbors.add_neighbor(0, i as u32);
+        }
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        // With symmetric neighbors, forces should roughly cancel out
+        unsafe {
+            let (sep_x, sep_y) = buffer.get_separation_force(0);
+            // Forces won't be exactly zero due to the arrangement, but should be small
+            assert!(
+                sep_x.abs() < 1.0 && sep_y.abs() < 1.0,
+                "Symmetric neighbors should partially cancel"
+            );
+        }
+    }
+
+    #[test]
+    fn test_boundary_steering() {
+        let mut buffer = BoidsBuffer::new(4);
+        let obstacles = ObstacleList::new(0);
+
+        unsafe {
+            // Unit sitting right at the left edge of a [0, 10] x [0, 10] world
+            *buffer.positions_x.add(0) = 0.5;
+            *buffer.positions_y.add(0) = 5.0;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.world_bounds = Some(crate::simd::WorldBounds {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 10.0,
+            max_y: 10.0,
+            margin: 2.0,
+            turn_strength: 1.0,
+            mode: BoundaryMode::SteerAway,
+        });
+
+        compute_boundary_forces(&mut buffer, &obstacles, &params);
+
+        unsafe {
+            let (fx, fy) = buffer.get_boundary_force(0);
+            assert!(fx > 0.0, "unit near left edge should be steered right (+x)");
+            assert!(fy.abs() < 1e-6, "no y steering expected, unit is mid-height");
+        }
+    }
+
+    #[test]
+    fn test_boundary_wrap() {
+        let mut buffer = BoidsBuffer::new(4);
+        let obstacles = ObstacleList::new(0);
+
+        unsafe {
+            // Unit just past the right edge of a [0, 10] x [0, 10] world
+            *buffer.positions_x.add(0) = 10.5;
+            *buffer.positions_y.add(0) = 5.0;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.world_bounds = Some(crate::simd::WorldBounds {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 10.0,
+            max_y: 10.0,
+            margin: 2.0,
+            turn_strength: 1.0,
+            mode: BoundaryMode::Wrap,
+        });
+
+        compute_boundary_forces(&mut buffer, &obstacles, &params);
+
+        unsafe {
+            let x = *buffer.positions_x.add(0);
+            assert!((x - 0.5).abs() < 1e-5, "unit should teleport to the left edge, got {x}");
+        }
+    }
+
+    #[test]
+    fn test_boundary_bounce() {
+        let mut buffer = BoidsBuffer::new(4);
+        let obstacles = ObstacleList::new(0);
+
+        unsafe {
+            // Unit past the right edge, moving further right
+            *buffer.positions_x.add(0) = 10.5;
+            *buffer.positions_y.add(0) = 5.0;
+            *buffer.velocities_x.add(0) = 2.0;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.world_bounds = Some(crate::simd::WorldBounds {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 10.0,
+            max_y: 10.0,
+            margin: 2.0,
+            turn_strength: 1.0,
+            mode: BoundaryMode::Bounce,
+        });
+
+        compute_boundary_forces(&mut buffer, &obstacles, &params);
+
+        unsafe {
+            let x = *buffer.positions_x.add(0);
+            let vx = *buffer.velocities_x.add(0);
+            assert!((x - 10.0).abs() < 1e-5, "position should clamp to the edge, got {x}");
+            assert!(vx < 0.0, "velocity should reflect back inward (-x), got {vx}");
+        }
+    }
+
+    #[test]
+    fn test_layer_bounds_override() {
+        let mut buffer = BoidsBuffer::new(4);
+        let obstacles = ObstacleList::new(0);
+
+        unsafe {
+            // Unit on layer 1, outside the default [0, 10] world but inside
+            // layer 1's wider [0, 20] override
+            *buffer.positions_x.add(0) = 15.0;
+            *buffer.positions_y.add(0) = 5.0;
+            *buffer.layers.add(0) = 1;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.world_bounds = Some(crate::simd::WorldBounds {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 10.0,
+            max_y: 10.0,
+            margin: 2.0,
+            turn_strength: 1.0,
+            mode: BoundaryMode::SteerAway,
+        });
+        params.layer_bounds.push((
+            1,
+            crate::simd::WorldBounds {
+                min_x: 0.0,
+                min_y: 0.0,
+                max_x: 20.0,
+                max_y: 20.0,
+                margin: 2.0,
+                turn_strength: 1.0,
+                mode: BoundaryMode::SteerAway,
+            },
+        ));
+
+        compute_boundary_forces(&mut buffer, &obstacles, &params);
+
+        unsafe {
+            let (fx, fy) = buffer.get_boundary_force(0);
+            assert_eq!(fx, 0.0, "unit well inside its layer's override should feel no steering");
+            assert_eq!(fy, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_obstacle_repulsion() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut obstacles = ObstacleList::new(1);
+
+        unsafe {
+            *buffer.positions_x.add(0) = 1.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        unsafe {
+            let ptr = obstacles.data_ptr_mut();
+            *ptr.add(0) = 0.0;
+            *ptr.add(1) = 0.0;
+            *ptr.add(2) = 1.0;
+        }
+        obstacles.set_count(1);
+
+        let params = BoidsParams::default();
+        compute_boundary_forces(&mut buffer, &obstacles, &params);
+
+        unsafe {
+            let (fx, fy) = buffer.get_boundary_force(0);
+            assert!(fx > 0.0, "unit should be pushed away from obstacle (+x)");
+            assert!(fy.abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_seek_force_follows_flow_direction() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut flow_field = FlowField::new(4, 1, 1.0, 0.0, 0.0, 1);
+
+        unsafe {
+            std::ptr::write_bytes(flow_field.passable_ptr_mut(), 1, 4);
+            *flow_field.goal_cells_ptr_mut().add(0) = 3;
+        }
+        flow_field.set_goal_count(1);
+        flow_field.build();
+
+        unsafe {
+            *buffer.positions_x.add(0) = 0.5;
+            *buffer.positions_y.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.seek_strength = 2.0;
+        compute_seek_force(&mut buffer, &flow_field, &params);
+
+        unsafe {
+            let (fx, fy) = buffer.get_seek_force(0);
+            assert!(fx > 0.0, "unit should be steered toward the goal (+x), got {fx}");
+            assert!(fy.abs() < 1e-6, "no y steering expected on a flat row, got {fy}");
+        }
+    }
+
+    #[test]
+    fn test_seek_force_zero_outside_flow_field() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut flow_field = FlowField::new(4, 1, 1.0, 0.0, 0.0, 1);
+
+        unsafe {
+            std::ptr::write_bytes(flow_field.passable_ptr_mut(), 1, 4);
+            *flow_field.goal_cells_ptr_mut().add(0) = 3;
+        }
+        flow_field.set_goal_count(1);
+        flow_field.build();
+
+        unsafe {
+            // Well outside the field's [0, 4) x [0, 1) extent
+            *buffer.positions_x.add(0) = 100.0;
+            *buffer.positions_y.add(0) = 100.0;
+            *buffer.states.add(0) = UnitState::Active as u8;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.seek_strength = 2.0;
+        compute_seek_force(&mut buffer, &flow_field, &params);
+
+        unsafe {
+            let (fx, fy) = buffer.get_seek_force(0);
+            assert_eq!(fx, 0.0, "no seek force expected outside the flow field");
+            assert_eq!(fy, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_integrate_advances_position() {
+        let mut buffer = BoidsBuffer::new(4);
+
+        unsafe {
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.force_coh_x.add(0) = 1.0;
+        }
+        buffer.set_count(1);
+
+        let params = BoidsParams::default();
+        integrate(&mut buffer, &params, 1.0);
+
+        unsafe {
+            let (vx, _) = buffer.get_velocity(0);
+            assert!(vx > 0.0, "unit should speed up in +x under a +x force");
+            let (px, _) = buffer.get_position(0);
+            assert!(px > 0.0, "unit should have moved in +x");
+        }
+    }
+
+    #[test]
+    fn test_integrate_clamps_to_max_speed() {
+        let mut buffer = BoidsBuffer::new(4);
+
+        unsafe {
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.force_coh_x.add(0) = 100.0;
+        }
+        buffer.set_count(1);
+
+        let mut params = BoidsParams::default();
+        params.max_force = 100.0;
+        params.max_speed = 2.0;
+        integrate(&mut buffer, &params, 1.0);
+
+        unsafe {
+            let (vx, vy) = buffer.get_velocity(0);
+            let speed = (vx * vx + vy * vy).sqrt();
+            assert!(speed <= params.max_speed + 1e-4, "speed {speed} should be clamped");
+        }
+    }
+
+    #[test]
+    fn test_golden_vector_matches_simd_separation() {
+        // Golden vector: two units 0.5 apart with unit radii 0.5 and default
+        // params should always push apart by the same magnitude regardless
+        // of which backend (scalar here, SIMD on wasm32) computed it.
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+        }
+
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            let (sep_x, _) = buffer.get_separation_force(0);
+            // combined_r=1.0, sep_dist=1.0, dist=0.5 -> strength = 1.5 * (1 - 0.5) = 0.75
+            assert!((sep_x + 0.75).abs() < 1e-4, "sep_x = {sep_x}");
+        }
+    }
+
+    #[test]
+    fn test_anisotropic_separation_strengthens_end_on_push() {
+        // Two elongated units (kappa=4) facing +x, positioned end-on along
+        // x at a distance the isotropic radius barely reaches. Anisotropy
+        // should grow the effective contact distance for this end-on pair,
+        // strengthening the separation push at the same physical distance.
+        let mut isotropic_buffer = BoidsBuffer::new(4);
+        let mut aniso_buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        for buffer in [&mut isotropic_buffer, &mut aniso_buffer] {
+            unsafe {
+                *buffer.positions_x.add(0) = 0.0;
+                *buffer.positions_y.add(0) = 0.0;
+                *buffer.radii.add(0) = 0.5;
+                *buffer.states.add(0) = UnitState::Active as u8;
+                *buffer.orientation_x.add(0) = 1.0;
+                *buffer.aspect_ratio.add(0) = 4.0;
+
+                *buffer.positions_x.add(1) = 0.9;
+                *buffer.positions_y.add(1) = 0.0;
+                *buffer.radii.add(1) = 0.5;
+                *buffer.states.add(1) = UnitState::Active as u8;
+                *buffer.orientation_x.add(1) = 1.0;
+                *buffer.aspect_ratio.add(1) = 4.0;
+            }
+            buffer.set_count(2);
+        }
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let mut params = BoidsParams::default();
+        compute_all_forces(&mut isotropic_buffer, &neighbors, &params);
+        params.anisotropic_separation = true;
+        compute_all_forces(&mut aniso_buffer, &neighbors, &params);
+
+        unsafe {
+            let (iso_sep_x, _) = isotropic_buffer.get_separation_force(0);
+            let (aniso_sep_x, _) = aniso_buffer.get_separation_force(0);
+            assert!(
+                aniso_sep_x.abs() > iso_sep_x.abs(),
+                "anisotropic end-on push ({aniso_sep_x}) should exceed isotropic ({iso_sep_x})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_anisotropic_separation_circular_units_unchanged() {
+        // kappa=1.0 (the default) on both sides must recover the isotropic
+        // force exactly, even with `anisotropic_separation` enabled.
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            *buffer.positions_x.add(0) = 0.0;
+            *buffer.positions_y.add(0) = 0.0;
+            *buffer.radii.add(0) = 0.5;
+            *buffer.states.add(0) = UnitState::Active as u8;
+            *buffer.aspect_ratio.add(0) = 1.0;
+
+            *buffer.positions_x.add(1) = 0.5;
+            *buffer.positions_y.add(1) = 0.0;
+            *buffer.radii.add(1) = 0.5;
+            *buffer.states.add(1) = UnitState::Active as u8;
+            *buffer.aspect_ratio.add(1) = 1.0;
+        }
+        buffer.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let mut params = BoidsParams::default();
+        params.anisotropic_separation = true;
+        compute_all_forces(&mut buffer, &neighbors, &params);
+
+        unsafe {
+            let (sep_x, _) = buffer.get_separation_force(0);
+            // Same golden value as test_golden_vector_matches_simd_separation.
+            assert!((sep_x + 0.75).abs() < 1e-4, "sep_x = {sep_x}");
+        }
+    }
+
+    #[test]
+    fn test_half_list_matches_full_list() {
+        // The half-list kernel should produce the same forces as the
+        // full-list kernel up to float rounding (sums accumulate in a
+        // different order), since it's the same pairwise math reused via
+        // Newton's third law rather than a different approximation.
+        let mut full_buffer = BoidsBuffer::new(8);
+        let mut half_buffer = BoidsBuffer::new(8);
+        let mut full_neighbors = NeighborList::new(8);
+        let mut half_neighbors = NeighborList::new(8);
+
+        let positions = [(0.0, 0.0), (0.5, 0.0), (0.0, 0.4), (3.0, 3.0)];
+        let velocities = [(1.0, 0.0), (0.8, 0.2), (0.0, 1.0), (-1.0, 0.5)];
+        for (i, (x, y)) in positions.iter().enumerate() {
+            let (vx, vy) = velocities[i];
+            unsafe {
+                *full_buffer.positions_x.add(i) = *x;
+                *full_buffer.positions_y.add(i) = *y;
+                *full_buffer.radii.add(i) = 0.5;
+                *full_buffer.velocities_x.add(i) = vx;
+                *full_buffer.velocities_y.add(i) = vy;
+                *full_buffer.states.add(i) = UnitState::Active as u8;
+                *half_buffer.positions_x.add(i) = *x;
+                *half_buffer.positions_y.add(i) = *y;
+                *half_buffer.radii.add(i) = 0.5;
+                *half_buffer.velocities_x.add(i) = vx;
+                *half_buffer.velocities_y.add(i) = vy;
+                *half_buffer.states.add(i) = UnitState::Active as u8;
+            }
+        }
+        full_buffer.set_count(4);
+        half_buffer.set_count(4);
+
+        for i in 0..4 {
+            full_neighbors.begin_unit(i);
+            for j in 0..4 {
+                if j != i {
+                    full_neighbors.add_neighbor(i, j as u32);
+                }
+            }
+        }
+        half_neighbors.build_from_grid_half(&half_buffer, 10.0);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut full_buffer, &full_neighbors, &params);
+        compute_all_forces_half(&mut half_buffer, &half_neighbors, &params);
+
+        unsafe {
+            for i in 0..4 {
+                let (full_sep_x, full_sep_y) = full_buffer.get_separation_force(i);
+                let (half_sep_x, half_sep_y) = half_buffer.get_separation_force(i);
+                assert!((full_sep_x - half_sep_x).abs() < 1e-4, "unit {i} sep_x diverged");
+                assert!((full_sep_y - half_sep_y).abs() < 1e-4, "unit {i} sep_y diverged");
+
+                let (full_coh_x, full_coh_y) = full_buffer.get_cohesion_force(i);
+                let (half_coh_x, half_coh_y) = half_buffer.get_cohesion_force(i);
+                assert!((full_coh_x - half_coh_x).abs() < 1e-4, "unit {i} coh_x diverged");
+                assert!((full_coh_y - half_coh_y).abs() < 1e-4, "unit {i} coh_y diverged");
+
+                let (full_align_x, full_align_y) = full_buffer.get_alignment_force(i);
+                let (half_align_x, half_align_y) = half_buffer.get_alignment_force(i);
+                assert!((full_align_x - half_align_x).abs() < 1e-4, "unit {i} align_x diverged");
+                assert!((full_align_y - half_align_y).abs() < 1e-4, "unit {i} align_y diverged");
+            }
+        }
+    }
+
+    #[test]
+    fn test_half_list_excludes_worker_and_gathering_from_all_three_forces() {
+        // Three widely-separated pairs (so cross-pair neighbors never occur):
+        // an Active/Active pair (forces expected), a Worker/Worker pair, and
+        // a Gathering/Gathering pair. Keeping both sides of each excluded
+        // pair in the *same* state keeps the full list's per-unit skip
+        // symmetric, so it's directly comparable to the half list's combined
+        // pair-level `valid` check. The full-list path's single `continue`
+        // drops separation, cohesion, *and* alignment together, so the
+        // half-list path must match on all three, not just separation.
+        let mut full_buffer = BoidsBuffer::new(8);
+        let mut half_buffer = BoidsBuffer::new(8);
+        let mut full_neighbors = NeighborList::new(8);
+        let mut half_neighbors = NeighborList::new(8);
+
+        let positions = [(0.0, 0.0), (0.3, 0.0), (100.0, 0.0), (100.3, 0.0), (200.0, 0.0), (200.3, 0.0)];
+        let velocities = [(1.0, 0.0), (0.8, 0.2), (1.0, 0.0), (0.8, 0.2), (1.0, 0.0), (0.8, 0.2)];
+        let states = [
+            UnitState::Active,
+            UnitState::Active,
+            UnitState::Worker,
+            UnitState::Worker,
+            UnitState::Gathering,
+            UnitState::Gathering,
+        ];
+        for (i, (x, y)) in positions.iter().enumerate() {
+            let (vx, vy) = velocities[i];
+            unsafe {
+                *full_buffer.positions_x.add(i) = *x;
+                *full_buffer.positions_y.add(i) = *y;
+                *full_buffer.radii.add(i) = 0.5;
+                *full_buffer.velocities_x.add(i) = vx;
+                *full_buffer.velocities_y.add(i) = vy;
+                *full_buffer.states.add(i) = states[i] as u8;
+                *half_buffer.positions_x.add(i) = *x;
+                *half_buffer.positions_y.add(i) = *y;
+                *half_buffer.radii.add(i) = 0.5;
+                *half_buffer.velocities_x.add(i) = vx;
+                *half_buffer.velocities_y.add(i) = vy;
+                *half_buffer.states.add(i) = states[i] as u8;
+            }
+        }
+        full_buffer.set_count(6);
+        half_buffer.set_count(6);
+
+        // Full list: only pair up units within the same group, mirroring what
+        // a real spatial grid would hand back (no cross-group neighbors).
+        let groups: [&[usize]; 3] = [&[0, 1], &[2, 3], &[4, 5]];
+        for &group in &groups {
+            for &i in group {
+                full_neighbors.begin_unit(i);
+                for &j in group {
+                    if j != i {
+                        full_neighbors.add_neighbor(i, j as u32);
+                    }
+                }
+            }
+        }
+        half_neighbors.build_from_grid_half(&half_buffer, 10.0);
+
+        let params = BoidsParams::default();
+        compute_all_forces(&mut full_buffer, &full_neighbors, &params);
+        compute_all_forces_half(&mut half_buffer, &half_neighbors, &params);
+
+        unsafe {
+            for i in 0..6 {
+                let (full_sep_x, full_sep_y) = full_buffer.get_separation_force(i);
+                let (half_sep_x, half_sep_y) = half_buffer.get_separation_force(i);
+                assert!((full_sep_x - half_sep_x).abs() < 1e-4, "unit {i} sep_x diverged");
+                assert!((full_sep_y - half_sep_y).abs() < 1e-4, "unit {i} sep_y diverged");
+
+                let (full_coh_x, full_coh_y) = full_buffer.get_cohesion_force(i);
+                let (half_coh_x, half_coh_y) = half_buffer.get_cohesion_force(i);
+                assert!((full_coh_x - half_coh_x).abs() < 1e-4, "unit {i} coh_x diverged");
+                assert!((full_coh_y - half_coh_y).abs() < 1e-4, "unit {i} coh_y diverged");
+
+                let (full_align_x, full_align_y) = full_buffer.get_alignment_force(i);
+                let (half_align_x, half_align_y) = half_buffer.get_alignment_force(i);
+                assert!((full_align_x - half_align_x).abs() < 1e-4, "unit {i} align_x diverged");
+                assert!((full_align_y - half_align_y).abs() < 1e-4, "unit {i} align_y diverged");
+            }
+
+            // The worker-worker and gathering-gathering pairs must be fully
+            // suppressed on all three forces, not just separation.
+            for &i in &[2usize, 3, 4, 5] {
+                let (coh_x, coh_y) = half_buffer.get_cohesion_force(i);
+                let (align_x, align_y) = half_buffer.get_alignment_force(i);
+                assert_eq!((coh_x, coh_y), (0.0, 0.0), "unit {i} cohesion should be suppressed");
+                assert_eq!((align_x, align_y), (0.0, 0.0), "unit {i} alignment should be suppressed");
+            }
+            // The active/active pair, in contrast, should have picked up a
+            // real alignment force (same heading, within radius).
+            let (align_x, _) = half_buffer.get_alignment_force(0);
+            assert!(align_x.abs() > 1e-4, "unit 0 alignment should be nonzero");
+        }
+    }
+}
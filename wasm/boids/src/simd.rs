@@ -8,16 +8,66 @@
 //! - **Separation**: Units push away from nearby units to avoid overlap
 //! - **Cohesion**: Units steer toward the center of mass of nearby units
 //! - **Alignment**: Units match the heading of nearby units
+//! - **Boundary/obstacles**: Units steer away from world edges and static
+//!   circular obstacles (`compute_boundary_forces_simd`)
+//!
+//! `compute_all_forces_simd` batches a unit's scattered neighbor *indices*
+//! via `gather_f32x4`. `compute_all_forces_simd_clustered` is an alternate
+//! entry point that batches neighbor *clusters* instead, trading the gather
+//! for a single aligned load per cluster (see `soa::ClusterNeighborList`).
 //!
 //! All operations use squared distances where possible to avoid sqrt.
 
 #[cfg(target_arch = "wasm32")]
 use std::arch::wasm32::*;
 
-use crate::soa::{BoidsBuffer, NeighborList, UnitState};
+use crate::soa::{BoidsBuffer, ClusterNeighborList, NeighborList, ObstacleList, UnitState, CLUSTER_SIZE};
+
+/// How a unit is kept inside `WorldBounds`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryMode {
+    /// Accumulate an inward steering force that ramps from 0 to
+    /// `turn_strength` over `margin` world units near each edge, written to
+    /// `force_bound_x/y` alongside separation/cohesion/alignment
+    SteerAway = 0,
+    /// Teleport the unit to the opposite edge (position modulo the rect),
+    /// like an asteroids-style wraparound play area
+    Wrap = 1,
+    /// Reflect the velocity component normal to whichever edge was crossed,
+    /// clamping position back inside the rect
+    Bounce = 2,
+}
 
-/// Boids parameters matching the game's RTS-style values
+impl BoundaryMode {
+    /// Decode a mode value from JS; unrecognized values fall back to
+    /// `SteerAway` (the pre-existing, only behavior before this enum existed)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => BoundaryMode::Wrap,
+            2 => BoundaryMode::Bounce,
+            _ => BoundaryMode::SteerAway,
+        }
+    }
+}
+
+/// Axis-aligned world bounds for containment
+///
+/// `margin` and `turn_strength` only matter for `BoundaryMode::SteerAway`;
+/// `Wrap` and `Bounce` act on position/velocity directly instead.
 #[derive(Clone, Copy, Debug)]
+pub struct WorldBounds {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+    pub margin: f32,
+    pub turn_strength: f32,
+    pub mode: BoundaryMode,
+}
+
+/// Boids parameters matching the game's RTS-style values
+#[derive(Clone, Debug)]
 pub struct BoidsParams {
     /// Radius within which separation force applies
     pub separation_radius: f32,
@@ -38,6 +88,64 @@ pub struct BoidsParams {
 
     /// Minimum speed to consider a unit as "moving" for alignment
     pub min_moving_speed: f32,
+
+    /// Default world containment bounds, applied to any unit whose layer
+    /// has no entry in `layer_bounds`; `None` disables boundary containment
+    /// entirely for such units.
+    pub world_bounds: Option<WorldBounds>,
+
+    /// Per-layer overrides of `world_bounds` (e.g. a taller rect for a
+    /// flying layer than for ground units). Only consulted by
+    /// `scalar::compute_boundary_forces`, which already walks units one at a
+    /// time; `compute_boundary_forces_simd` applies `world_bounds` uniformly
+    /// to every unit regardless of layer. Small in practice (a handful of
+    /// layers), so a linear scan beats the bookkeeping of a sparse map.
+    pub layer_bounds: Vec<(u8, WorldBounds)>,
+
+    /// Use a Gay-Berne-style orientation-dependent contact distance for
+    /// separation instead of the isotropic `combined_r * separation_radius`.
+    /// Reads `BoidsBuffer::orientation_x/y` and `aspect_ratio`. Off by
+    /// default so round-unit formations don't pay for the extra per-pair
+    /// dot products; only honored by `compute_all_forces`/
+    /// `compute_all_forces_simd` and the half-list `compute_all_forces_half`
+    /// (the clustered and native wide-SIMD kernels still use isotropic
+    /// separation).
+    pub anisotropic_separation: bool,
+
+    /// Magnitude of the goal-seeking steering force, scaling the unit
+    /// direction vector sampled from the active `FlowField` (see
+    /// `scalar::compute_seek_force`). 0 disables seeking even if a flow
+    /// field is set.
+    pub seek_strength: f32,
+
+    /// Maximum magnitude of the combined steering acceleration (separation +
+    /// cohesion + alignment + boundary/obstacle + seek), applied before integration
+    pub max_force: f32,
+    /// Maximum speed a unit can reach after integration
+    pub max_speed: f32,
+    /// Minimum speed a moving unit is held to (0 disables the floor)
+    pub min_speed: f32,
+}
+
+impl BoidsParams {
+    /// Recommended cell size for the spatial hash grid: the largest of the
+    /// three interaction radii, so a 3x3 block of cells is guaranteed to
+    /// cover every unit that could actually interact with a given unit.
+    pub fn max_interaction_radius(&self) -> f32 {
+        self.separation_radius
+            .max(self.cohesion_radius)
+            .max(self.alignment_radius)
+    }
+
+    /// The bounds that apply to a unit on the given layer: its `layer_bounds`
+    /// override if one exists, else the default `world_bounds`.
+    pub fn bounds_for_layer(&self, layer: u8) -> Option<&WorldBounds> {
+        self.layer_bounds
+            .iter()
+            .find(|(l, _)| *l == layer)
+            .map(|(_, bounds)| bounds)
+            .or(self.world_bounds.as_ref())
+    }
 }
 
 impl Default for BoidsParams {
@@ -55,10 +163,111 @@ impl Default for BoidsParams {
             alignment_strength: 0.3,
 
             min_moving_speed: 0.1,
+
+            world_bounds: None,
+            layer_bounds: Vec::new(),
+            anisotropic_separation: false,
+            seek_strength: 0.0,
+
+            max_force: 3.0,
+            max_speed: 5.0,
+            min_speed: 0.0,
         }
     }
 }
 
+/// Gay-Berne-style effective contact distance for anisotropic separation,
+/// used in place of the isotropic `sigma0` (= `combined_r * separation_radius`)
+/// when `BoidsParams::anisotropic_separation` is set. Shared by the scalar
+/// and SIMD kernels so both compute bit-identical results.
+///
+/// `rx`/`ry` is the (unit-length) separation direction between the pair,
+/// `uix`/`uiy` and `ujx`/`ujy` are each unit's heading (assumed
+/// pre-normalized by the caller -- e.g. derived from velocity or facing
+/// angle in JS), and `kappa_i`/`kappa_j` are the pair's per-unit
+/// length-to-width ratios. Both kappas are clamped up to `1.0` (the zeroed
+/// buffer default collapses to circular, not degenerate), and the pair is
+/// combined as `kappa_i * kappa_j` -- the square of their geometric mean --
+/// so two circular units (`kappa == 1`) recover `sigma0` exactly, while a
+/// mixed pair inherits anisotropy from whichever unit is more elongated.
+#[inline]
+pub fn anisotropic_sigma(
+    sigma0: f32,
+    rx: f32,
+    ry: f32,
+    uix: f32,
+    uiy: f32,
+    ujx: f32,
+    ujy: f32,
+    kappa_i: f32,
+    kappa_j: f32,
+) -> f32 {
+    let kappa_sq = kappa_i.max(1.0) * kappa_j.max(1.0);
+    let chi = (kappa_sq - 1.0) / (kappa_sq + 1.0);
+
+    let r_dot_ui = rx * uix + ry * uiy;
+    let r_dot_uj = rx * ujx + ry * ujy;
+    let ui_dot_uj = uix * ujx + uiy * ujy;
+
+    let sum = r_dot_ui + r_dot_uj;
+    let diff = r_dot_ui - r_dot_uj;
+    let sum_term = (sum * sum) / (1.0 + chi * ui_dot_uj).max(0.0001);
+    let diff_term = (diff * diff) / (1.0 - chi * ui_dot_uj).max(0.0001);
+
+    let denom = (1.0 - 0.5 * chi * (sum_term + diff_term)).max(0.0001);
+    sigma0 / denom.sqrt()
+}
+
+/// Teleport a single unit's position modulo `bounds`' rect
+/// (`BoundaryMode::Wrap`). Shared by the scalar and SIMD boundary kernels.
+///
+/// # Safety
+/// `i` must be a valid index into `buffer`.
+#[inline]
+pub unsafe fn apply_boundary_wrap_unit(buffer: &mut BoidsBuffer, bounds: &WorldBounds, i: usize) {
+    let width = (bounds.max_x - bounds.min_x).max(0.0001);
+    let height = (bounds.max_y - bounds.min_y).max(0.0001);
+    let x = *buffer.positions_x.add(i);
+    let y = *buffer.positions_y.add(i);
+    *buffer.positions_x.add(i) = bounds.min_x + (x - bounds.min_x).rem_euclid(width);
+    *buffer.positions_y.add(i) = bounds.min_y + (y - bounds.min_y).rem_euclid(height);
+}
+
+/// Reflect a single unit's velocity off whichever edge of `bounds` it
+/// crossed and clamp its position back inside (`BoundaryMode::Bounce`).
+/// Shared by the scalar and SIMD boundary kernels.
+///
+/// # Safety
+/// `i` must be a valid index into `buffer`.
+#[inline]
+pub unsafe fn apply_boundary_bounce_unit(buffer: &mut BoidsBuffer, bounds: &WorldBounds, i: usize) {
+    let mut x = *buffer.positions_x.add(i);
+    let mut y = *buffer.positions_y.add(i);
+    let mut vx = *buffer.velocities_x.add(i);
+    let mut vy = *buffer.velocities_y.add(i);
+
+    if x < bounds.min_x {
+        x = bounds.min_x;
+        vx = vx.abs();
+    } else if x > bounds.max_x {
+        x = bounds.max_x;
+        vx = -vx.abs();
+    }
+
+    if y < bounds.min_y {
+        y = bounds.min_y;
+        vy = vy.abs();
+    } else if y > bounds.max_y {
+        y = bounds.max_y;
+        vy = -vy.abs();
+    }
+
+    *buffer.positions_x.add(i) = x;
+    *buffer.positions_y.add(i) = y;
+    *buffer.velocities_x.add(i) = vx;
+    *buffer.velocities_y.add(i) = vy;
+}
+
 /// SIMD vector operations for batch neighbor processing
 #[cfg(target_arch = "wasm32")]
 pub mod vector_ops {
@@ -156,6 +365,66 @@ pub mod vector_ops {
     pub unsafe fn apply_mask(v: v128, mask: v128) -> v128 {
         v128_and(v, mask)
     }
+
+    /// Pack a mask's 4 lanes into the low 4 bits of a `u32`, one bit per
+    /// lane (mirrors `i32x4_bitmask`). Used for cheap movemask-style
+    /// all-zero/any-true tests without reducing the whole vector.
+    #[inline]
+    pub unsafe fn lane_mask_bits(mask: v128) -> u32 {
+        i32x4_bitmask(mask) as u32
+    }
+
+    /// Whether any lane of a mask is set
+    #[inline]
+    pub unsafe fn any_true(mask: v128) -> bool {
+        lane_mask_bits(mask) != 0
+    }
+
+    /// Vectorized form of `simd::anisotropic_sigma`: computes the 4 pairs'
+    /// worth of `r·u_i`, `r·u_j`, `u_i·u_j` dot products (and combined
+    /// kappa-squared) as `f32x4` lanes instead of 4 scalar calls.
+    #[inline]
+    pub unsafe fn anisotropic_sigma_4(
+        sigma0: v128,
+        rx: v128,
+        ry: v128,
+        uix: v128,
+        uiy: v128,
+        ujx: v128,
+        ujy: v128,
+        kappa_i: v128,
+        kappa_j: v128,
+    ) -> v128 {
+        let one = f32x4_splat(1.0);
+        let half = f32x4_splat(0.5);
+        let epsilon = f32x4_splat(0.0001);
+
+        let ki = f32x4_max(kappa_i, one);
+        let kj = f32x4_max(kappa_j, one);
+        let kappa_sq = f32x4_mul(ki, kj);
+        let chi = f32x4_div(f32x4_sub(kappa_sq, one), f32x4_add(kappa_sq, one));
+
+        let r_dot_ui = f32x4_add(f32x4_mul(rx, uix), f32x4_mul(ry, uiy));
+        let r_dot_uj = f32x4_add(f32x4_mul(rx, ujx), f32x4_mul(ry, ujy));
+        let ui_dot_uj = f32x4_add(f32x4_mul(uix, ujx), f32x4_mul(uiy, ujy));
+
+        let sum = f32x4_add(r_dot_ui, r_dot_uj);
+        let diff = f32x4_sub(r_dot_ui, r_dot_uj);
+
+        let sum_term = f32x4_div(
+            f32x4_mul(sum, sum),
+            f32x4_max(f32x4_add(one, f32x4_mul(chi, ui_dot_uj)), epsilon),
+        );
+        let diff_term = f32x4_div(
+            f32x4_mul(diff, diff),
+            f32x4_max(f32x4_sub(one, f32x4_mul(chi, ui_dot_uj)), epsilon),
+        );
+
+        let bracket = f32x4_add(sum_term, diff_term);
+        let denom = f32x4_max(f32x4_sub(one, f32x4_mul(half, f32x4_mul(chi, bracket))), epsilon);
+
+        f32x4_div(sigma0, f32x4_sqrt(denom))
+    }
 }
 
 /// Compute all boids forces for all units using SIMD
@@ -244,7 +513,6 @@ fn compute_unit_forces_simd(
         let ux = *buffer.positions_x.add(unit_idx);
         let uy = *buffer.positions_y.add(unit_idx);
         let ur = *buffer.radii.add(unit_idx);
-        let unit_layer = *buffer.layers.add(unit_idx);
 
         // SIMD accumulators for force components
         let mut sep_x_acc = f32x4_splat(0.0);
@@ -260,6 +528,9 @@ fn compute_unit_forces_simd(
         let ux4 = f32x4_splat(ux);
         let uy4 = f32x4_splat(uy);
         let ur4 = f32x4_splat(ur);
+        let u_orient_x4 = f32x4_splat(*buffer.orientation_x.add(unit_idx));
+        let u_orient_y4 = f32x4_splat(*buffer.orientation_y.add(unit_idx));
+        let u_kappa4 = f32x4_splat(*buffer.aspect_ratio.add(unit_idx));
 
         // Params as SIMD vectors
         let sep_radius = f32x4_splat(params.separation_radius);
@@ -271,6 +542,7 @@ fn compute_unit_forces_simd(
         let one = f32x4_splat(1.0);
 
         let neighbor_slice = neighbors.get_neighbors(unit_idx);
+        let neighbor_valid_mask = neighbors.get_valid_mask(unit_idx);
         let neighbor_count = neighbor_slice.len();
         let simd_count = neighbor_count / 4 * 4;
 
@@ -281,12 +553,28 @@ fn compute_unit_forces_simd(
             let n2 = neighbor_slice[batch_start + 2] as usize;
             let n3 = neighbor_slice[batch_start + 3] as usize;
 
-            // Build validity mask for all skip conditions
-            let valid0 = is_valid_neighbor(buffer, unit_idx, unit_state, unit_layer, n0);
-            let valid1 = is_valid_neighbor(buffer, unit_idx, unit_state, unit_layer, n1);
-            let valid2 = is_valid_neighbor(buffer, unit_idx, unit_state, unit_layer, n2);
-            let valid3 = is_valid_neighbor(buffer, unit_idx, unit_state, unit_layer, n3);
-            let valid_mask = mask_from_bools(valid0, valid1, valid2, valid3);
+            // Load the precomputed self/dead/layer/worker-worker/gathering
+            // mask for this batch in one shot (see `NeighborList::valid_mask`)
+            // instead of 4 branchy `is_valid_neighbor` calls, then AND in the
+            // live per-unit override so a neighbor that died or started
+            // gathering since the list was last built is still excluded.
+            let precomputed_mask =
+                v128_load(neighbor_valid_mask.as_ptr().add(batch_start) as *const v128);
+            let override_mask = mask_from_bools(
+                *buffer.separation_suppressed.add(n0) == 0,
+                *buffer.separation_suppressed.add(n1) == 0,
+                *buffer.separation_suppressed.add(n2) == 0,
+                *buffer.separation_suppressed.add(n3) == 0,
+            );
+            let valid_mask = v128_and(precomputed_mask, override_mask);
+
+            // Movemask-style early-out: in crowded scenes most batches are
+            // dominated by dead/cross-layer/worker-worker/gathering
+            // neighbors, so skip the gathers and math entirely rather than
+            // computing a batch of forces that the mask would zero anyway.
+            if !any_true(valid_mask) {
+                continue;
+            }
 
             // Gather neighbor positions
             let nx4 = gather_f32x4(buffer.positions_x, n0, n1, n2, n3);
@@ -299,9 +587,31 @@ fn compute_unit_forces_simd(
             let dist_sq = distance_squared_4(ux4, uy4, nx4, ny4);
 
             // === SEPARATION ===
-            // Separation distance is proportional to combined unit sizes
+            // Separation distance is proportional to combined unit sizes,
+            // unless `anisotropic_separation` swaps in the Gay-Berne-style
+            // orientation-dependent contact distance (see
+            // `anisotropic_sigma_4`); the isotropic math below is otherwise
+            // unchanged and this branch is checked once per batch of 4, not
+            // per lane, so round-unit formations don't pay for the extra dot
+            // products.
             let combined_r = f32x4_add(ur4, nr4);
-            let sep_dist = f32x4_mul(combined_r, sep_radius);
+            let sigma0 = f32x4_mul(combined_r, sep_radius);
+            let sep_dist = if params.anisotropic_separation {
+                let n_orient_x4 = gather_f32x4(buffer.orientation_x, n0, n1, n2, n3);
+                let n_orient_y4 = gather_f32x4(buffer.orientation_y, n0, n1, n2, n3);
+                let n_kappa4 = gather_f32x4(buffer.aspect_ratio, n0, n1, n2, n3);
+
+                let dir_dist = f32x4_sqrt(f32x4_max(dist_sq, epsilon));
+                let inv_dir_dist = f32x4_div(one, dir_dist);
+                let rx4 = f32x4_mul(dx4, inv_dir_dist);
+                let ry4 = f32x4_mul(dy4, inv_dir_dist);
+
+                anisotropic_sigma_4(
+                    sigma0, rx4, ry4, u_orient_x4, u_orient_y4, n_orient_x4, n_orient_y4, u_kappa4, n_kappa4,
+                )
+            } else {
+                sigma0
+            };
             let sep_dist_sq = f32x4_mul(sep_dist, sep_dist);
 
             // Check if in separation range (dist < sep_dist && dist > epsilon)
@@ -311,38 +621,51 @@ fn compute_unit_forces_simd(
             );
             let sep_mask = v128_and(valid_mask, in_sep_range);
 
-            // Compute separation force: strength * (1 - dist/sep_dist) * normalized_direction
-            let dist = f32x4_sqrt(f32x4_max(dist_sq, epsilon));
-            let inv_dist = f32x4_div(one, dist);
-            let strength = f32x4_mul(
-                sep_strength,
-                f32x4_sub(one, f32x4_div(dist, sep_dist)),
-            );
-
-            let sep_fx = f32x4_mul(f32x4_mul(dx4, inv_dist), strength);
-            let sep_fy = f32x4_mul(f32x4_mul(dy4, inv_dist), strength);
-
-            sep_x_acc = f32x4_add(sep_x_acc, apply_mask(sep_fx, sep_mask));
-            sep_y_acc = f32x4_add(sep_y_acc, apply_mask(sep_fy, sep_mask));
+            // Skip the sqrt/div of the separation force entirely when no
+            // lane in this batch is actually within separation range
+            if any_true(sep_mask) {
+                // Compute separation force: strength * (1 - dist/sep_dist) * normalized_direction
+                let dist = f32x4_sqrt(f32x4_max(dist_sq, epsilon));
+                let inv_dist = f32x4_div(one, dist);
+                let strength = f32x4_mul(
+                    sep_strength,
+                    f32x4_sub(one, f32x4_div(dist, sep_dist)),
+                );
+
+                let sep_fx = f32x4_mul(f32x4_mul(dx4, inv_dist), strength);
+                let sep_fy = f32x4_mul(f32x4_mul(dy4, inv_dist), strength);
+
+                sep_x_acc = f32x4_add(sep_x_acc, apply_mask(sep_fx, sep_mask));
+                sep_y_acc = f32x4_add(sep_y_acc, apply_mask(sep_fy, sep_mask));
+            }
 
             // === COHESION ===
             // Accumulate neighbor positions for center-of-mass calculation
             let in_coh_range = f32x4_lt(dist_sq, coh_radius_sq);
             let coh_mask = v128_and(valid_mask, in_coh_range);
 
-            coh_x_acc = f32x4_add(coh_x_acc, apply_mask(nx4, coh_mask));
-            coh_y_acc = f32x4_add(coh_y_acc, apply_mask(ny4, coh_mask));
-            coh_count_acc = f32x4_add(coh_count_acc, apply_mask(one, coh_mask));
+            if any_true(coh_mask) {
+                coh_x_acc = f32x4_add(coh_x_acc, apply_mask(nx4, coh_mask));
+                coh_y_acc = f32x4_add(coh_y_acc, apply_mask(ny4, coh_mask));
+                coh_count_acc = f32x4_add(coh_count_acc, apply_mask(one, coh_mask));
+            }
 
             // === ALIGNMENT ===
+            // Skip the velocity gather and normalization entirely when no
+            // lane is both in range and valid -- "is it moving fast enough"
+            // still needs the gather, so that check happens inside.
+            let in_align_range = v128_and(valid_mask, f32x4_lt(dist_sq, align_radius_sq));
+            if !any_true(in_align_range) {
+                continue;
+            }
+
             // Accumulate normalized neighbor velocities
             let nvx4 = gather_f32x4(buffer.velocities_x, n0, n1, n2, n3);
             let nvy4 = gather_f32x4(buffer.velocities_y, n0, n1, n2, n3);
             let speed_sq = f32x4_add(f32x4_mul(nvx4, nvx4), f32x4_mul(nvy4, nvy4));
 
-            let in_align_range = f32x4_lt(dist_sq, align_radius_sq);
             let is_moving = f32x4_gt(speed_sq, min_speed_sq);
-            let align_mask = v128_and(v128_and(valid_mask, in_align_range), is_moving);
+            let align_mask = v128_and(in_align_range, is_moving);
 
             // Normalize velocities
             let speed = f32x4_sqrt(f32x4_max(speed_sq, epsilon));
@@ -369,7 +692,7 @@ fn compute_unit_forces_simd(
         for i in simd_count..neighbor_count {
             let ni = neighbor_slice[i] as usize;
 
-            if !is_valid_neighbor(buffer, unit_idx, unit_state, unit_layer, ni) {
+            if neighbor_valid_mask[i] == 0 || *buffer.separation_suppressed.add(ni) != 0 {
                 continue;
             }
 
@@ -383,7 +706,25 @@ fn compute_unit_forces_simd(
 
             // Separation
             let combined_r = ur + nr;
-            let sep_dist = combined_r * params.separation_radius;
+            let sigma0 = combined_r * params.separation_radius;
+            let sep_dist = if params.anisotropic_separation && dist_sq > 0.0001 {
+                let dist = dist_sq.sqrt();
+                let rx = dx / dist;
+                let ry = dy / dist;
+                anisotropic_sigma(
+                    sigma0,
+                    rx,
+                    ry,
+                    *buffer.orientation_x.add(unit_idx),
+                    *buffer.orientation_y.add(unit_idx),
+                    *buffer.orientation_x.add(ni),
+                    *buffer.orientation_y.add(ni),
+                    *buffer.aspect_ratio.add(unit_idx),
+                    *buffer.aspect_ratio.add(ni),
+                )
+            } else {
+                sigma0
+            };
             let sep_dist_sq = sep_dist * sep_dist;
 
             if dist_sq < sep_dist_sq && dist_sq > 0.0001 {
@@ -455,429 +796,637 @@ fn compute_unit_forces_simd(
     }
 }
 
-/// Scalar fallback for individual units (used in non-WASM builds)
-fn compute_forces_scalar(
+/// Cluster-based variant of `compute_all_forces_simd`.
+///
+/// The per-unit kernel's inner loop gathers 4 scattered neighbor indices
+/// with `gather_f32x4` (4 scalar loads) for every batch. Here the neighbor
+/// list is cluster-to-cluster (see `ClusterNeighborList`), so each neighbor
+/// cluster's positions/radii/velocities are loaded once with a single
+/// aligned `v128_load` and reused across the 1x4 interaction block for
+/// every live lane in the home cluster, instead of re-gathering per home
+/// unit. The force math itself (separation/cohesion/alignment accumulation)
+/// is the same as `compute_unit_forces_simd`'s, except that separation is
+/// always isotropic (`anisotropic_separation` is not read) and neighbor
+/// validity is rechecked live with `is_valid_neighbor` instead of via
+/// `valid_mask`/`separation_suppressed`.
+#[cfg(target_arch = "wasm32")]
+pub fn compute_all_forces_simd_clustered(
     buffer: &mut BoidsBuffer,
-    neighbors: &NeighborList,
+    clusters: &ClusterNeighborList,
     params: &BoidsParams,
-    unit_idx: usize,
 ) {
-    unsafe {
-        let unit_state = *buffer.states.add(unit_idx);
-        let unit_layer = *buffer.layers.add(unit_idx);
-
-        // Skip dead/inactive units
-        if unit_state == UnitState::Dead as u8 {
-            return;
-        }
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
 
-        let ux = *buffer.positions_x.add(unit_idx);
-        let uy = *buffer.positions_y.add(unit_idx);
-        let ur = *buffer.radii.add(unit_idx);
+    buffer.zero_forces();
 
-        let mut sep_x = 0.0f32;
-        let mut sep_y = 0.0f32;
-        let mut coh_sum_x = 0.0f32;
-        let mut coh_sum_y = 0.0f32;
-        let mut coh_count = 0.0f32;
-        let mut align_sum_vx = 0.0f32;
-        let mut align_sum_vy = 0.0f32;
-        let mut align_count = 0.0f32;
+    for home_cluster in 0..clusters.cluster_count() {
+        let home_base = home_cluster * CLUSTER_SIZE;
+        if home_base >= count {
+            continue;
+        }
 
-        // Iterate over neighbors
-        for &neighbor_idx in neighbors.get_neighbors(unit_idx) {
-            let ni = neighbor_idx as usize;
+        let neighbor_clusters = clusters.get_cluster_neighbors(home_cluster);
+        if neighbor_clusters.is_empty() {
+            continue;
+        }
 
-            if ni == unit_idx {
-                continue;
+        for lane in 0..CLUSTER_SIZE {
+            let unit_idx = home_base + lane;
+            if unit_idx >= count {
+                break;
             }
 
-            let neighbor_state = *buffer.states.add(ni);
-            if neighbor_state == UnitState::Dead as u8 {
-                continue;
+            unsafe {
+                compute_unit_forces_simd_against_clusters(
+                    buffer,
+                    neighbor_clusters,
+                    params,
+                    unit_idx,
+                    count,
+                );
             }
+        }
+    }
+}
 
-            let neighbor_layer = *buffer.layers.add(ni);
-            if neighbor_layer != unit_layer {
-                continue;
-            }
+/// Check if a neighbor *lane* within a loaded cluster should be processed,
+/// same rules as `is_valid_neighbor` plus a bounds check since a cluster's
+/// trailing lanes can run past `count` (capacity is rounded up to a
+/// multiple of `CLUSTER_SIZE`, but the live unit count isn't)
+#[cfg(target_arch = "wasm32")]
+#[inline]
+unsafe fn is_valid_cluster_lane(
+    buffer: &BoidsBuffer,
+    unit_idx: usize,
+    unit_state: u8,
+    unit_layer: u8,
+    neighbor_idx: usize,
+    count: usize,
+) -> bool {
+    if neighbor_idx >= count {
+        return false;
+    }
+    is_valid_neighbor(buffer, unit_idx, unit_state, unit_layer, neighbor_idx)
+}
 
-            if unit_state == UnitState::Worker as u8
-                && neighbor_state == UnitState::Worker as u8
-            {
-                continue;
-            }
+/// Compute one home unit's forces against every member of its cluster's
+/// neighbor clusters, using 1x4 aligned-load SIMD batches
+#[cfg(target_arch = "wasm32")]
+unsafe fn compute_unit_forces_simd_against_clusters(
+    buffer: &mut BoidsBuffer,
+    neighbor_clusters: &[u32],
+    params: &BoidsParams,
+    unit_idx: usize,
+    count: usize,
+) {
+    use vector_ops::*;
 
-            if neighbor_state == UnitState::Gathering as u8 {
-                continue;
-            }
+    let unit_state = *buffer.states.add(unit_idx);
+    if unit_state == UnitState::Dead as u8 {
+        return;
+    }
 
-            let nx = *buffer.positions_x.add(ni);
-            let ny = *buffer.positions_y.add(ni);
-            let nr = *buffer.radii.add(ni);
+    let ux = *buffer.positions_x.add(unit_idx);
+    let uy = *buffer.positions_y.add(unit_idx);
+    let ur = *buffer.radii.add(unit_idx);
+    let unit_layer = *buffer.layers.add(unit_idx);
+
+    let mut sep_x_acc = f32x4_splat(0.0);
+    let mut sep_y_acc = f32x4_splat(0.0);
+    let mut coh_x_acc = f32x4_splat(0.0);
+    let mut coh_y_acc = f32x4_splat(0.0);
+    let mut coh_count_acc = f32x4_splat(0.0);
+    let mut align_vx_acc = f32x4_splat(0.0);
+    let mut align_vy_acc = f32x4_splat(0.0);
+    let mut align_count_acc = f32x4_splat(0.0);
+
+    let ux4 = f32x4_splat(ux);
+    let uy4 = f32x4_splat(uy);
+    let ur4 = f32x4_splat(ur);
+
+    let sep_radius = f32x4_splat(params.separation_radius);
+    let sep_strength = f32x4_splat(params.separation_strength);
+    let coh_radius_sq = f32x4_splat(params.cohesion_radius * params.cohesion_radius);
+    let align_radius_sq = f32x4_splat(params.alignment_radius * params.alignment_radius);
+    let min_speed_sq = f32x4_splat(params.min_moving_speed * params.min_moving_speed);
+    let epsilon = f32x4_splat(0.0001);
+    let one = f32x4_splat(1.0);
+
+    for &neighbor_cluster in neighbor_clusters {
+        let nbase = neighbor_cluster as usize * CLUSTER_SIZE;
+
+        let valid0 = is_valid_cluster_lane(buffer, unit_idx, unit_state, unit_layer, nbase, count);
+        let valid1 = is_valid_cluster_lane(buffer, unit_idx, unit_state, unit_layer, nbase + 1, count);
+        let valid2 = is_valid_cluster_lane(buffer, unit_idx, unit_state, unit_layer, nbase + 2, count);
+        let valid3 = is_valid_cluster_lane(buffer, unit_idx, unit_state, unit_layer, nbase + 3, count);
+        let valid_mask = mask_from_bools(valid0, valid1, valid2, valid3);
+
+        // Aligned contiguous loads: `nbase` is always a multiple of
+        // `CLUSTER_SIZE`, and the SoA arrays are allocated with
+        // `CLUSTER_SIZE`-aligned capacity, so no gather is needed here.
+        let nx4 = v128_load(buffer.positions_x.add(nbase) as *const v128);
+        let ny4 = v128_load(buffer.positions_y.add(nbase) as *const v128);
+        let nr4 = v128_load(buffer.radii.add(nbase) as *const v128);
+
+        let dx4 = f32x4_sub(ux4, nx4);
+        let dy4 = f32x4_sub(uy4, ny4);
+        let dist_sq = distance_squared_4(ux4, uy4, nx4, ny4);
+
+        // === SEPARATION ===
+        let combined_r = f32x4_add(ur4, nr4);
+        let sep_dist = f32x4_mul(combined_r, sep_radius);
+        let sep_dist_sq = f32x4_mul(sep_dist, sep_dist);
+
+        let in_sep_range = v128_and(f32x4_lt(dist_sq, sep_dist_sq), f32x4_gt(dist_sq, epsilon));
+        let sep_mask = v128_and(valid_mask, in_sep_range);
+
+        let dist = f32x4_sqrt(f32x4_max(dist_sq, epsilon));
+        let inv_dist = f32x4_div(one, dist);
+        let strength = f32x4_mul(sep_strength, f32x4_sub(one, f32x4_div(dist, sep_dist)));
+
+        let sep_fx = f32x4_mul(f32x4_mul(dx4, inv_dist), strength);
+        let sep_fy = f32x4_mul(f32x4_mul(dy4, inv_dist), strength);
+
+        sep_x_acc = f32x4_add(sep_x_acc, apply_mask(sep_fx, sep_mask));
+        sep_y_acc = f32x4_add(sep_y_acc, apply_mask(sep_fy, sep_mask));
+
+        // === COHESION ===
+        let in_coh_range = f32x4_lt(dist_sq, coh_radius_sq);
+        let coh_mask = v128_and(valid_mask, in_coh_range);
+
+        coh_x_acc = f32x4_add(coh_x_acc, apply_mask(nx4, coh_mask));
+        coh_y_acc = f32x4_add(coh_y_acc, apply_mask(ny4, coh_mask));
+        coh_count_acc = f32x4_add(coh_count_acc, apply_mask(one, coh_mask));
+
+        // === ALIGNMENT ===
+        let nvx4 = v128_load(buffer.velocities_x.add(nbase) as *const v128);
+        let nvy4 = v128_load(buffer.velocities_y.add(nbase) as *const v128);
+        let speed_sq = f32x4_add(f32x4_mul(nvx4, nvx4), f32x4_mul(nvy4, nvy4));
+
+        let in_align_range = f32x4_lt(dist_sq, align_radius_sq);
+        let is_moving = f32x4_gt(speed_sq, min_speed_sq);
+        let align_mask = v128_and(v128_and(valid_mask, in_align_range), is_moving);
+
+        let speed = f32x4_sqrt(f32x4_max(speed_sq, epsilon));
+        let inv_speed = f32x4_div(one, speed);
+        let norm_vx = f32x4_mul(nvx4, inv_speed);
+        let norm_vy = f32x4_mul(nvy4, inv_speed);
+
+        align_vx_acc = f32x4_add(align_vx_acc, apply_mask(norm_vx, align_mask));
+        align_vy_acc = f32x4_add(align_vy_acc, apply_mask(norm_vy, align_mask));
+        align_count_acc = f32x4_add(align_count_acc, apply_mask(one, align_mask));
+    }
 
-            let dx = ux - nx;
-            let dy = uy - ny;
-            let dist_sq = dx * dx + dy * dy;
+    let mut sep_x = horizontal_sum(sep_x_acc);
+    let mut sep_y = horizontal_sum(sep_y_acc);
+    let coh_sum_x = horizontal_sum(coh_x_acc);
+    let coh_sum_y = horizontal_sum(coh_y_acc);
+    let coh_count = horizontal_sum(coh_count_acc);
+    let align_sum_vx = horizontal_sum(align_vx_acc);
+    let align_sum_vy = horizontal_sum(align_vy_acc);
+    let align_count = horizontal_sum(align_count_acc);
+
+    let sep_mag_sq = sep_x * sep_x + sep_y * sep_y;
+    if sep_mag_sq > params.max_separation_force * params.max_separation_force {
+        let scale = params.max_separation_force / sep_mag_sq.sqrt();
+        sep_x *= scale;
+        sep_y *= scale;
+    }
 
-            let combined_r = ur + nr;
-            let sep_dist = combined_r * params.separation_radius;
-            let sep_dist_sq = sep_dist * sep_dist;
+    *buffer.force_sep_x.add(unit_idx) = sep_x;
+    *buffer.force_sep_y.add(unit_idx) = sep_y;
 
-            // Separation
-            if dist_sq < sep_dist_sq && dist_sq > 0.0001 {
-                let dist = dist_sq.sqrt();
-                let strength = params.separation_strength * (1.0 - dist / sep_dist);
-                sep_x += (dx / dist) * strength;
-                sep_y += (dy / dist) * strength;
-            }
+    if coh_count > 0.0 {
+        let center_x = coh_sum_x / coh_count;
+        let center_y = coh_sum_y / coh_count;
+        let to_center_x = center_x - ux;
+        let to_center_y = center_y - uy;
+        let dist = (to_center_x * to_center_x + to_center_y * to_center_y).sqrt();
 
-            // Cohesion
-            if dist_sq < params.cohesion_radius * params.cohesion_radius {
-                coh_sum_x += nx;
-                coh_sum_y += ny;
-                coh_count += 1.0;
-            }
+        if dist > 0.1 {
+            *buffer.force_coh_x.add(unit_idx) = (to_center_x / dist) * params.cohesion_strength;
+            *buffer.force_coh_y.add(unit_idx) = (to_center_y / dist) * params.cohesion_strength;
+        }
+    }
 
-            // Alignment
-            if dist_sq < params.alignment_radius * params.alignment_radius {
-                let nvx = *buffer.velocities_x.add(ni);
-                let nvy = *buffer.velocities_y.add(ni);
-                let speed_sq = nvx * nvx + nvy * nvy;
+    if align_count > 0.0 {
+        let avg_vx = align_sum_vx / align_count;
+        let avg_vy = align_sum_vy / align_count;
+        let mag = (avg_vx * avg_vx + avg_vy * avg_vy).sqrt();
 
-                if speed_sq > params.min_moving_speed * params.min_moving_speed {
-                    let speed = speed_sq.sqrt();
-                    align_sum_vx += nvx / speed;
-                    align_sum_vy += nvy / speed;
-                    align_count += 1.0;
-                }
-            }
+        if mag > 0.1 {
+            *buffer.force_align_x.add(unit_idx) = (avg_vx / mag) * params.alignment_strength;
+            *buffer.force_align_y.add(unit_idx) = (avg_vy / mag) * params.alignment_strength;
         }
+    }
+}
 
-        // Clamp separation
-        let sep_mag_sq = sep_x * sep_x + sep_y * sep_y;
-        if sep_mag_sq > params.max_separation_force * params.max_separation_force {
-            let scale = params.max_separation_force / sep_mag_sq.sqrt();
-            sep_x *= scale;
-            sep_y *= scale;
-        }
+/// Compute world-bounds containment and static-obstacle avoidance forces
+///
+/// Unlike separation/cohesion/alignment, which batch a *unit's neighbor
+/// list* 4 at a time, here the edges and obstacle list are the same for
+/// every unit, so batching instead runs over 4 *units* at a time against
+/// each edge/obstacle. Results accumulate into `force_bound_x/y`.
+#[cfg(target_arch = "wasm32")]
+pub fn compute_boundary_forces_simd(
+    buffer: &mut BoidsBuffer,
+    obstacles: &ObstacleList,
+    params: &BoidsParams,
+) {
+    let count = buffer.len();
+    if count == 0 {
+        return;
+    }
 
-        *buffer.force_sep_x.add(unit_idx) = sep_x;
-        *buffer.force_sep_y.add(unit_idx) = sep_y;
+    unsafe {
+        std::ptr::write_bytes(buffer.force_bound_x, 0, buffer.capacity());
+        std::ptr::write_bytes(buffer.force_bound_y, 0, buffer.capacity());
+    }
 
-        // Cohesion
-        if coh_count > 0.0 {
-            let center_x = coh_sum_x / coh_count;
-            let center_y = coh_sum_y / coh_count;
-            let to_center_x = center_x - ux;
-            let to_center_y = center_y - uy;
-            let dist = (to_center_x * to_center_x + to_center_y * to_center_y).sqrt();
+    let simd_count = count / 4 * 4;
 
-            if dist > 0.1 {
-                *buffer.force_coh_x.add(unit_idx) = (to_center_x / dist) * params.cohesion_strength;
-                *buffer.force_coh_y.add(unit_idx) = (to_center_y / dist) * params.cohesion_strength;
+    unsafe {
+        if let Some(bounds) = params.world_bounds {
+            // `Wrap`/`Bounce` mutate position/velocity directly rather than
+            // accumulating a force, so they don't benefit from batching --
+            // only `SteerAway` below gets the SIMD treatment. Per-layer
+            // bounds (`BoidsParams::layer_bounds`) aren't honored here since
+            // a single rect applies uniformly to the whole batch; use
+            // `scalar::compute_boundary_forces` if units need different
+            // bounds per layer.
+            match bounds.mode {
+                BoundaryMode::Wrap => {
+                    for i in 0..count {
+                        if *buffer.states.add(i) != UnitState::Dead as u8 {
+                            apply_boundary_wrap_unit(buffer, &bounds, i);
+                        }
+                    }
+                    return compute_obstacle_forces_simd(buffer, obstacles, params, simd_count, count);
+                }
+                BoundaryMode::Bounce => {
+                    for i in 0..count {
+                        if *buffer.states.add(i) != UnitState::Dead as u8 {
+                            apply_boundary_bounce_unit(buffer, &bounds, i);
+                        }
+                    }
+                    return compute_obstacle_forces_simd(buffer, obstacles, params, simd_count, count);
+                }
+                BoundaryMode::SteerAway => {}
             }
-        }
 
-        // Alignment
-        if align_count > 0.0 {
-            let avg_vx = align_sum_vx / align_count;
-            let avg_vy = align_sum_vy / align_count;
-            let mag = (avg_vx * avg_vx + avg_vy * avg_vy).sqrt();
+            let margin = bounds.margin.max(0.0001);
+            let min_x4 = f32x4_splat(bounds.min_x);
+            let min_y4 = f32x4_splat(bounds.min_y);
+            let max_x4 = f32x4_splat(bounds.max_x);
+            let max_y4 = f32x4_splat(bounds.max_y);
+            let margin4 = f32x4_splat(margin);
+            let turn_strength4 = f32x4_splat(bounds.turn_strength);
+            let zero = f32x4_splat(0.0);
+            let one = f32x4_splat(1.0);
+
+            for batch_start in (0..simd_count).step_by(4) {
+                let alive_mask = dead_mask_4(buffer, batch_start);
+
+                let px = v128_load(buffer.positions_x.add(batch_start) as *const v128);
+                let py = v128_load(buffer.positions_y.add(batch_start) as *const v128);
+
+                // Penetration into the margin band at each edge, clamped to
+                // [0, 1]; 0 outside the band, 1 past the edge.
+                let t_left = f32x4_max(
+                    zero,
+                    f32x4_min(one, f32x4_div(f32x4_sub(margin4, f32x4_sub(px, min_x4)), margin4)),
+                );
+                let t_right = f32x4_max(
+                    zero,
+                    f32x4_min(one, f32x4_div(f32x4_sub(margin4, f32x4_sub(max_x4, px)), margin4)),
+                );
+                let t_bottom = f32x4_max(
+                    zero,
+                    f32x4_min(one, f32x4_div(f32x4_sub(margin4, f32x4_sub(py, min_y4)), margin4)),
+                );
+                let t_top = f32x4_max(
+                    zero,
+                    f32x4_min(one, f32x4_div(f32x4_sub(margin4, f32x4_sub(max_y4, py)), margin4)),
+                );
+
+                let fx = v128_and(f32x4_mul(f32x4_sub(t_left, t_right), turn_strength4), alive_mask);
+                let fy = v128_and(f32x4_mul(f32x4_sub(t_bottom, t_top), turn_strength4), alive_mask);
+
+                let bx = v128_load(buffer.force_bound_x.add(batch_start) as *const v128);
+                let by = v128_load(buffer.force_bound_y.add(batch_start) as *const v128);
+                v128_store(buffer.force_bound_x.add(batch_start) as *mut v128, f32x4_add(bx, fx));
+                v128_store(buffer.force_bound_y.add(batch_start) as *mut v128, f32x4_add(by, fy));
+            }
 
-            if mag > 0.1 {
-                *buffer.force_align_x.add(unit_idx) = (avg_vx / mag) * params.alignment_strength;
-                *buffer.force_align_y.add(unit_idx) = (avg_vy / mag) * params.alignment_strength;
+            for i in simd_count..count {
+                if *buffer.states.add(i) == UnitState::Dead as u8 {
+                    continue;
+                }
+                let x = *buffer.positions_x.add(i);
+                let y = *buffer.positions_y.add(i);
+
+                let t_left = ((margin - (x - bounds.min_x)) / margin).clamp(0.0, 1.0);
+                let t_right = ((margin - (bounds.max_x - x)) / margin).clamp(0.0, 1.0);
+                let t_bottom = ((margin - (y - bounds.min_y)) / margin).clamp(0.0, 1.0);
+                let t_top = ((margin - (bounds.max_y - y)) / margin).clamp(0.0, 1.0);
+
+                *buffer.force_bound_x.add(i) += (t_left - t_right) * bounds.turn_strength;
+                *buffer.force_bound_y.add(i) += (t_bottom - t_top) * bounds.turn_strength;
             }
         }
+
+        compute_obstacle_forces_simd(buffer, obstacles, params, simd_count, count);
     }
 }
 
-/// Non-WASM fallback (for testing on native platforms)
-#[cfg(not(target_arch = "wasm32"))]
-pub fn compute_all_forces_simd(
+/// Radial push away from each circular obstacle, scaled the same way
+/// separation is (strength ramps to `max_separation_force`). Accumulates
+/// into `force_bound_x/y` alongside whatever `compute_boundary_forces_simd`
+/// already wrote there for world-bounds containment.
+///
+/// # Safety
+/// `simd_count` must be `count / 4 * 4` and both must match `buffer.len()`.
+#[cfg(target_arch = "wasm32")]
+unsafe fn compute_obstacle_forces_simd(
     buffer: &mut BoidsBuffer,
-    neighbors: &NeighborList,
+    obstacles: &ObstacleList,
     params: &BoidsParams,
+    simd_count: usize,
+    count: usize,
 ) {
-    let count = buffer.len();
-    if count == 0 {
-        return;
-    }
+    let epsilon = f32x4_splat(0.0001);
+    let one = f32x4_splat(1.0);
+    let max_force4 = f32x4_splat(params.max_separation_force);
 
-    buffer.zero_forces();
+    for obstacle_idx in 0..obstacles.len() {
+        let (ox, oy, oradius) = obstacles.get(obstacle_idx);
+        let ox4 = f32x4_splat(ox);
+        let oy4 = f32x4_splat(oy);
+        let oradius4 = f32x4_splat(oradius);
 
-    for i in 0..count {
-        compute_forces_scalar(buffer, neighbors, params, i);
-    }
-}
+        for batch_start in (0..simd_count).step_by(4) {
+            let alive_mask = dead_mask_4(buffer, batch_start);
 
-/// Check if WASM SIMD is available at runtime
-#[cfg(target_arch = "wasm32")]
-pub fn simd_available() -> bool {
-    true
-}
+            let px = v128_load(buffer.positions_x.add(batch_start) as *const v128);
+            let py = v128_load(buffer.positions_y.add(batch_start) as *const v128);
+            let pr = v128_load(buffer.radii.add(batch_start) as *const v128);
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn simd_available() -> bool {
-    false
-}
+            let dx = f32x4_sub(px, ox4);
+            let dy = f32x4_sub(py, oy4);
+            let dist_sq = f32x4_add(f32x4_mul(dx, dx), f32x4_mul(dy, dy));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+            let contact = f32x4_add(pr, oradius4);
+            let contact_sq = f32x4_mul(contact, contact);
+            let in_range = v128_and(
+                alive_mask,
+                v128_and(f32x4_lt(dist_sq, contact_sq), f32x4_gt(dist_sq, epsilon)),
+            );
 
-    #[test]
-    fn test_default_params() {
-        let params = BoidsParams::default();
-        assert_eq!(params.separation_radius, 1.0);
-        assert_eq!(params.cohesion_radius, 8.0);
-        assert_eq!(params.alignment_radius, 4.0);
-    }
+            let dist = f32x4_sqrt(f32x4_max(dist_sq, epsilon));
+            let inv_dist = f32x4_div(one, dist);
+            let strength = f32x4_mul(max_force4, f32x4_sub(one, f32x4_div(dist, contact)));
 
-    #[test]
-    fn test_scalar_separation() {
-        let mut buffer = BoidsBuffer::new(4);
-        let mut neighbors = NeighborList::new(4);
-
-        unsafe {
-            // Set up two units close together
-            *buffer.positions_x.add(0) = 0.0;
-            *buffer.positions_y.add(0) = 0.0;
-            *buffer.radii.add(0) = 0.5;
-            *buffer.states.add(0) = UnitState::Active as u8;
-            *buffer.layers.add(0) = 0;
-
-            *buffer.positions_x.add(1) = 0.5;
-            *buffer.positions_y.add(1) = 0.0;
-            *buffer.radii.add(1) = 0.5;
-            *buffer.states.add(1) = UnitState::Active as u8;
-            *buffer.layers.add(1) = 0;
+            let push_x = v128_and(f32x4_mul(f32x4_mul(dx, inv_dist), strength), in_range);
+            let push_y = v128_and(f32x4_mul(f32x4_mul(dy, inv_dist), strength), in_range);
+
+            let bx = v128_load(buffer.force_bound_x.add(batch_start) as *const v128);
+            let by = v128_load(buffer.force_bound_y.add(batch_start) as *const v128);
+            v128_store(buffer.force_bound_x.add(batch_start) as *mut v128, f32x4_add(bx, push_x));
+            v128_store(buffer.force_bound_y.add(batch_start) as *mut v128, f32x4_add(by, push_y));
         }
 
-        buffer.set_count(2);
+        for i in simd_count..count {
+            if *buffer.states.add(i) == UnitState::Dead as u8 {
+                continue;
+            }
+            let x = *buffer.positions_x.add(i);
+            let y = *buffer.positions_y.add(i);
+            let r = *buffer.radii.add(i);
 
-        // Set up neighbors
-        neighbors.begin_unit(0);
-        neighbors.add_neighbor(0, 1);
-        neighbors.begin_unit(1);
-        neighbors.add_neighbor(1, 0);
+            let dx = x - ox;
+            let dy = y - oy;
+            let dist_sq = dx * dx + dy * dy;
+            let contact = r + oradius;
+            let contact_sq = contact * contact;
 
-        let params = BoidsParams::default();
-        compute_all_forces_simd(&mut buffer, &neighbors, &params);
-
-        unsafe {
-            // Unit 0 should be pushed left (negative x)
-            let (sep_x, sep_y) = buffer.get_separation_force(0);
-            assert!(sep_x < 0.0, "Unit 0 should be pushed left");
-            assert!(sep_y.abs() < 0.01, "No Y separation expected");
-
-            // Unit 1 should be pushed right (positive x)
-            let (sep_x, sep_y) = buffer.get_separation_force(1);
-            assert!(sep_x > 0.0, "Unit 1 should be pushed right");
-            assert!(sep_y.abs() < 0.01, "No Y separation expected");
+            if dist_sq < contact_sq && dist_sq > 0.0001 {
+                let dist = dist_sq.sqrt();
+                let strength = params.max_separation_force * (1.0 - dist / contact);
+                *buffer.force_bound_x.add(i) += (dx / dist) * strength;
+                *buffer.force_bound_y.add(i) += (dy / dist) * strength;
+            }
         }
     }
+}
 
-    #[test]
-    fn test_cohesion_force() {
-        let mut buffer = BoidsBuffer::new(8);
-        let mut neighbors = NeighborList::new(8);
-
-        unsafe {
-            // Unit 0 at origin
-            *buffer.positions_x.add(0) = 0.0;
-            *buffer.positions_y.add(0) = 0.0;
-            *buffer.radii.add(0) = 0.5;
-            *buffer.states.add(0) = UnitState::Active as u8;
-            *buffer.layers.add(0) = 0;
-
-            // Unit 1 at (5, 0) - within cohesion radius (8)
-            *buffer.positions_x.add(1) = 5.0;
-            *buffer.positions_y.add(1) = 0.0;
-            *buffer.radii.add(1) = 0.5;
-            *buffer.states.add(1) = UnitState::Active as u8;
-            *buffer.layers.add(1) = 0;
-        }
-
-        buffer.set_count(2);
 
-        neighbors.begin_unit(0);
-        neighbors.add_neighbor(0, 1);
-        neighbors.begin_unit(1);
-        neighbors.add_neighbor(1, 0);
+/// Build a lane mask with `-1` for live (non-`Dead`) units, `0` for dead ones,
+/// for the 4 units starting at `batch_start`
+#[cfg(target_arch = "wasm32")]
+#[inline]
+unsafe fn dead_mask_4(buffer: &BoidsBuffer, batch_start: usize) -> v128 {
+    vector_ops::mask_from_bools(
+        *buffer.states.add(batch_start) != UnitState::Dead as u8,
+        *buffer.states.add(batch_start + 1) != UnitState::Dead as u8,
+        *buffer.states.add(batch_start + 2) != UnitState::Dead as u8,
+        *buffer.states.add(batch_start + 3) != UnitState::Dead as u8,
+    )
+}
 
-        let params = BoidsParams::default();
-        compute_all_forces_simd(&mut buffer, &neighbors, &params);
+/// Blend the accumulated forces into an acceleration, integrate velocity and
+/// position with semi-implicit Euler, and write the results back in-place.
+///
+/// Must run after `compute_all_forces_simd`/`compute_boundary_forces_simd`
+/// have populated the force arrays for this tick.
+#[cfg(target_arch = "wasm32")]
+pub fn integrate_simd(buffer: &mut BoidsBuffer, params: &BoidsParams, dt: f32) {
+    use vector_ops::clamp_magnitude_4;
 
-        unsafe {
-            // Unit 0 should be pulled toward unit 1 (positive x direction)
-            let (coh_x, coh_y) = buffer.get_cohesion_force(0);
-            assert!(coh_x > 0.0, "Unit 0 should be pulled right toward unit 1");
-            assert!(coh_y.abs() < 0.01, "No Y cohesion expected");
-        }
+    let count = buffer.len();
+    if count == 0 {
+        return;
     }
 
-    #[test]
-    fn test_alignment_force() {
-        let mut buffer = BoidsBuffer::new(8);
-        let mut neighbors = NeighborList::new(8);
-
-        unsafe {
-            // Unit 0 at origin, stationary
-            *buffer.positions_x.add(0) = 0.0;
-            *buffer.positions_y.add(0) = 0.0;
-            *buffer.velocities_x.add(0) = 0.0;
-            *buffer.velocities_y.add(0) = 0.0;
-            *buffer.radii.add(0) = 0.5;
-            *buffer.states.add(0) = UnitState::Active as u8;
-            *buffer.layers.add(0) = 0;
-
-            // Unit 1 at (2, 0), moving in +Y direction
-            *buffer.positions_x.add(1) = 2.0;
-            *buffer.positions_y.add(1) = 0.0;
-            *buffer.velocities_x.add(1) = 0.0;
-            *buffer.velocities_y.add(1) = 1.0;
-            *buffer.radii.add(1) = 0.5;
-            *buffer.states.add(1) = UnitState::Active as u8;
-            *buffer.layers.add(1) = 0;
-        }
+    let simd_count = count / 4 * 4;
+    let dt4 = f32x4_splat(dt);
+    let max_force4 = f32x4_splat(params.max_force);
+    let max_speed4 = f32x4_splat(params.max_speed);
+    let min_speed4 = f32x4_splat(params.min_speed);
+    let epsilon = f32x4_splat(0.0001);
 
-        buffer.set_count(2);
+    unsafe {
+        for batch_start in (0..simd_count).step_by(4) {
+            let alive_mask = dead_mask_4(buffer, batch_start);
+
+            let sum_x = f32x4_add(
+                f32x4_add(
+                    f32x4_add(
+                        v128_load(buffer.force_sep_x.add(batch_start) as *const v128),
+                        v128_load(buffer.force_coh_x.add(batch_start) as *const v128),
+                    ),
+                    f32x4_add(
+                        v128_load(buffer.force_align_x.add(batch_start) as *const v128),
+                        v128_load(buffer.force_bound_x.add(batch_start) as *const v128),
+                    ),
+                ),
+                v128_load(buffer.force_seek_x.add(batch_start) as *const v128),
+            );
+            let sum_y = f32x4_add(
+                f32x4_add(
+                    f32x4_add(
+                        v128_load(buffer.force_sep_y.add(batch_start) as *const v128),
+                        v128_load(buffer.force_coh_y.add(batch_start) as *const v128),
+                    ),
+                    f32x4_add(
+                        v128_load(buffer.force_align_y.add(batch_start) as *const v128),
+                        v128_load(buffer.force_bound_y.add(batch_start) as *const v128),
+                    ),
+                ),
+                v128_load(buffer.force_seek_y.add(batch_start) as *const v128),
+            );
 
-        neighbors.begin_unit(0);
-        neighbors.add_neighbor(0, 1);
-        neighbors.begin_unit(1);
-        neighbors.add_neighbor(1, 0);
+            let (ax, ay) = clamp_magnitude_4(sum_x, sum_y, max_force4);
 
-        let params = BoidsParams::default();
-        compute_all_forces_simd(&mut buffer, &neighbors, &params);
+            let vx = v128_load(buffer.velocities_x.add(batch_start) as *const v128);
+            let vy = v128_load(buffer.velocities_y.add(batch_start) as *const v128);
+            let nvx = f32x4_add(vx, f32x4_mul(ax, dt4));
+            let nvy = f32x4_add(vy, f32x4_mul(ay, dt4));
 
-        unsafe {
-            // Unit 0 should align with unit 1's velocity (positive y direction)
-            let (align_x, align_y) = buffer.get_alignment_force(0);
-            assert!(align_x.abs() < 0.01, "No X alignment expected");
-            assert!(align_y > 0.0, "Unit 0 should align toward +Y");
-        }
-    }
+            let (cvx, cvy) = clamp_magnitude_4(nvx, nvy, max_speed4);
 
-    #[test]
-    fn test_skip_dead_units() {
-        let mut buffer = BoidsBuffer::new(4);
-        let mut neighbors = NeighborList::new(4);
-
-        unsafe {
-            // Unit 0 active
-            *buffer.positions_x.add(0) = 0.0;
-            *buffer.positions_y.add(0) = 0.0;
-            *buffer.radii.add(0) = 0.5;
-            *buffer.states.add(0) = UnitState::Active as u8;
-            *buffer.layers.add(0) = 0;
-
-            // Unit 1 dead (should be skipped)
-            *buffer.positions_x.add(1) = 0.5;
-            *buffer.positions_y.add(1) = 0.0;
-            *buffer.radii.add(1) = 0.5;
-            *buffer.states.add(1) = UnitState::Dead as u8;
-            *buffer.layers.add(1) = 0;
+            // Floor the speed to `min_speed` (skip near-stationary units so we
+            // never divide by ~0 to "boost" a unit that isn't moving at all)
+            let speed_sq = f32x4_add(f32x4_mul(cvx, cvx), f32x4_mul(cvy, cvy));
+            let speed = f32x4_sqrt(f32x4_max(speed_sq, epsilon));
+            let below_min = v128_and(f32x4_lt(speed, min_speed4), f32x4_gt(speed, epsilon));
+            let boost_scale = f32x4_div(min_speed4, speed);
+            let fvx = v128_bitselect(f32x4_mul(cvx, boost_scale), cvx, below_min);
+            let fvy = v128_bitselect(f32x4_mul(cvy, boost_scale), cvy, below_min);
+
+            let out_vx = v128_bitselect(fvx, vx, alive_mask);
+            let out_vy = v128_bitselect(fvy, vy, alive_mask);
+            v128_store(buffer.velocities_x.add(batch_start) as *mut v128, out_vx);
+            v128_store(buffer.velocities_y.add(batch_start) as *mut v128, out_vy);
+
+            let px = v128_load(buffer.positions_x.add(batch_start) as *const v128);
+            let py = v128_load(buffer.positions_y.add(batch_start) as *const v128);
+            let new_px = v128_bitselect(f32x4_add(px, f32x4_mul(out_vx, dt4)), px, alive_mask);
+            let new_py = v128_bitselect(f32x4_add(py, f32x4_mul(out_vy, dt4)), py, alive_mask);
+            v128_store(buffer.positions_x.add(batch_start) as *mut v128, new_px);
+            v128_store(buffer.positions_y.add(batch_start) as *mut v128, new_py);
         }
 
-        buffer.set_count(2);
+        for i in simd_count..count {
+            integrate_unit_scalar(buffer, params, dt, i);
+        }
+    }
+}
 
-        neighbors.begin_unit(0);
-        neighbors.add_neighbor(0, 1);
+/// Scalar integration for a single unit, used for both the SIMD tail and
+/// (via `crate::scalar::integrate`) the fully native backend
+pub(crate) unsafe fn integrate_unit_scalar(buffer: &mut BoidsBuffer, params: &BoidsParams, dt: f32, i: usize) {
+    if *buffer.states.add(i) == UnitState::Dead as u8 {
+        return;
+    }
 
-        let params = BoidsParams::default();
-        compute_all_forces_simd(&mut buffer, &neighbors, &params);
+    let mut ax = *buffer.force_sep_x.add(i) + *buffer.force_coh_x.add(i)
+        + *buffer.force_align_x.add(i)
+        + *buffer.force_bound_x.add(i)
+        + *buffer.force_seek_x.add(i);
+    let mut ay = *buffer.force_sep_y.add(i) + *buffer.force_coh_y.add(i)
+        + *buffer.force_align_y.add(i)
+        + *buffer.force_bound_y.add(i)
+        + *buffer.force_seek_y.add(i);
+
+    let force_mag_sq = ax * ax + ay * ay;
+    if force_mag_sq > params.max_force * params.max_force {
+        let scale = params.max_force / force_mag_sq.sqrt();
+        ax *= scale;
+        ay *= scale;
+    }
 
-        unsafe {
-            // No forces should be applied since the only neighbor is dead
-            let (sep_x, sep_y) = buffer.get_separation_force(0);
-            assert_eq!(sep_x, 0.0, "No separation expected with dead neighbor");
-            assert_eq!(sep_y, 0.0, "No separation expected with dead neighbor");
-        }
+    let mut vx = *buffer.velocities_x.add(i) + ax * dt;
+    let mut vy = *buffer.velocities_y.add(i) + ay * dt;
+
+    let speed_sq = vx * vx + vy * vy;
+    if speed_sq > params.max_speed * params.max_speed {
+        let scale = params.max_speed / speed_sq.sqrt();
+        vx *= scale;
+        vy *= scale;
+    } else if speed_sq > 0.0001 && speed_sq < params.min_speed * params.min_speed {
+        let scale = params.min_speed / speed_sq.sqrt();
+        vx *= scale;
+        vy *= scale;
     }
 
-    #[test]
-    fn test_skip_different_layers() {
-        let mut buffer = BoidsBuffer::new(4);
-        let mut neighbors = NeighborList::new(4);
-
-        unsafe {
-            // Unit 0 on layer 0 (ground)
-            *buffer.positions_x.add(0) = 0.0;
-            *buffer.positions_y.add(0) = 0.0;
-            *buffer.radii.add(0) = 0.5;
-            *buffer.states.add(0) = UnitState::Active as u8;
-            *buffer.layers.add(0) = 0;
-
-            // Unit 1 on layer 1 (flying) - should be skipped
-            *buffer.positions_x.add(1) = 0.5;
-            *buffer.positions_y.add(1) = 0.0;
-            *buffer.radii.add(1) = 0.5;
-            *buffer.states.add(1) = UnitState::Active as u8;
-            *buffer.layers.add(1) = 1;
-        }
+    *buffer.velocities_x.add(i) = vx;
+    *buffer.velocities_y.add(i) = vy;
+    *buffer.positions_x.add(i) += vx * dt;
+    *buffer.positions_y.add(i) += vy * dt;
+}
 
-        buffer.set_count(2);
+/// Check if WASM SIMD is available at runtime
+#[cfg(target_arch = "wasm32")]
+pub fn simd_available() -> bool {
+    true
+}
 
-        neighbors.begin_unit(0);
-        neighbors.add_neighbor(0, 1);
+#[cfg(not(target_arch = "wasm32"))]
+pub fn simd_available() -> bool {
+    false
+}
 
-        let params = BoidsParams::default();
-        compute_all_forces_simd(&mut buffer, &neighbors, &params);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        unsafe {
-            // No forces should be applied since neighbor is on different layer
-            let (sep_x, sep_y) = buffer.get_separation_force(0);
-            assert_eq!(sep_x, 0.0, "No separation expected across layers");
-            assert_eq!(sep_y, 0.0, "No separation expected across layers");
-        }
+    #[test]
+    fn test_default_params() {
+        let params = BoidsParams::default();
+        assert_eq!(params.separation_radius, 1.0);
+        assert_eq!(params.cohesion_radius, 8.0);
+        assert_eq!(params.alignment_radius, 4.0);
+        assert!(!params.anisotropic_separation);
     }
 
     #[test]
-    fn test_many_neighbors() {
-        // Test with more than 4 neighbors to exercise SIMD batching + scalar tail
-        let mut buffer = BoidsBuffer::new(8);
-        let mut neighbors = NeighborList::new(8);
-
-        unsafe {
-            // Unit 0 at origin
-            *buffer.positions_x.add(0) = 0.0;
-            *buffer.positions_y.add(0) = 0.0;
-            *buffer.radii.add(0) = 0.5;
-            *buffer.states.add(0) = UnitState::Active as u8;
-            *buffer.layers.add(0) = 0;
-
-            // 6 neighbors surrounding unit 0
-            for i in 1..7 {
-                let angle = (i as f32) * std::f32::consts::PI / 3.0;
-                *buffer.positions_x.add(i) = 0.5 * angle.cos();
-                *buffer.positions_y.add(i) = 0.5 * angle.sin();
-                *buffer.radii.add(i) = 0.5;
-                *buffer.states.add(i) = UnitState::Active as u8;
-                *buffer.layers.add(i) = 0;
-            }
-        }
-
-        buffer.set_count(7);
+    fn test_anisotropic_sigma_kappa_one_matches_isotropic() {
+        let sigma0 = 2.0;
+        let sigma = anisotropic_sigma(sigma0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0);
+        assert!((sigma - sigma0).abs() < 1e-5, "sigma = {sigma}");
+    }
 
-        neighbors.begin_unit(0);
-        for i in 1..7 {
-            neighbors.add_neighbor(0, i as u32);
-        }
+    #[test]
+    fn test_anisotropic_sigma_end_on_exceeds_side_on() {
+        // Two elongated units (kappa=3), both facing +x.
+        let sigma0 = 2.0;
+        // End-on: separation direction r=(1,0) is aligned with heading.
+        let end_on = anisotropic_sigma(sigma0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 3.0, 3.0);
+        // Side-on: separation direction r=(0,1) is perpendicular to heading.
+        let side_on = anisotropic_sigma(sigma0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 3.0, 3.0);
+        assert!(end_on > side_on, "end-on sigma {end_on} should exceed side-on sigma {side_on}");
+        assert!((side_on - sigma0).abs() < 1e-5, "side-on sigma should equal the isotropic baseline");
+    }
 
-        let params = BoidsParams::default();
-        compute_all_forces_simd(&mut buffer, &neighbors, &params);
-
-        // With symmetric neighbors, forces should roughly cancel out
-        unsafe {
-            let (sep_x, sep_y) = buffer.get_separation_force(0);
-            // Forces won't be exactly zero due to the arrangement, but should be small
-            assert!(
-                sep_x.abs() < 1.0 && sep_y.abs() < 1.0,
-                "Symmetric neighbors should partially cancel"
-            );
-        }
+    #[test]
+    fn test_anisotropic_sigma_matches_explicit_major_minor_semi_axes() {
+        // `aspect_ratio` (kappa) is the major/minor semi-axis ratio of an
+        // ellipse whose minor axis is `sigma0`: this pins the two contact
+        // distances the Gay-Berne formula produces for a same-heading pair to
+        // the exact major/minor semi-axis lengths (`sigma0 * kappa` end-on,
+        // `sigma0` broadside), rather than just their relative ordering.
+        let sigma0 = 2.0;
+        let kappa = 3.0;
+        let radii_major = sigma0 * kappa;
+        let radii_minor = sigma0;
+
+        let end_on = anisotropic_sigma(sigma0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, kappa, kappa);
+        let side_on = anisotropic_sigma(sigma0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, kappa, kappa);
+
+        assert!((end_on - radii_major).abs() < 1e-4, "end-on contact distance {end_on} should equal the major semi-axis {radii_major}");
+        assert!((side_on - radii_minor).abs() < 1e-4, "side-on contact distance {side_on} should equal the minor semi-axis {radii_minor}");
     }
 }
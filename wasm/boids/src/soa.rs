@@ -17,9 +17,35 @@
 //! compared to AoS which would require 4 scattered loads.
 
 use std::alloc::{alloc_zeroed, dealloc, Layout};
+use std::collections::VecDeque;
 
-/// Alignment for SIMD operations (16 bytes = 4 x f32)
-const SIMD_ALIGNMENT: usize = 16;
+/// Default lane width for `BoidsBuffer::new`/`try_new`: f32x4, 16-byte alignment
+const DEFAULT_LANES: usize = 4;
+
+/// Errors from `BoidsBuffer::try_new`/`try_with_lanes`
+#[derive(Debug, PartialEq, Eq)]
+pub enum BufferAllocError {
+    /// `lanes` passed to `with_lanes`/`try_with_lanes` wasn't `4` or `8`
+    UnsupportedLaneWidth(usize),
+    /// The requested capacity, once rounded up to a multiple of the lane
+    /// width and sized into bytes, overflowed `usize` or was rejected by
+    /// `Layout::from_size_align`
+    CapacityOverflow,
+    /// The global allocator returned null for one of the buffer's arrays
+    AllocFailed,
+}
+
+impl std::fmt::Display for BufferAllocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BufferAllocError::UnsupportedLaneWidth(lanes) => write!(f, "BoidsBuffer lane width {} is not 4 or 8", lanes),
+            BufferAllocError::CapacityOverflow => write!(f, "BoidsBuffer capacity overflows a valid allocation layout"),
+            BufferAllocError::AllocFailed => write!(f, "BoidsBuffer allocation failed (out of memory)"),
+        }
+    }
+}
+
+impl std::error::Error for BufferAllocError {}
 
 /// Unit state flags for filtering during boids computation
 #[repr(u8)]
@@ -39,8 +65,18 @@ pub enum UnitState {
 
 /// SoA buffer for unit positions and velocities
 ///
-/// All arrays are SIMD-aligned (16-byte) for optimal vector load performance.
-/// Capacity is always rounded up to the nearest multiple of 4 for SIMD tail handling.
+/// All arrays are SIMD-aligned for optimal vector load performance --
+/// 16-byte (f32x4) by default, or 32-byte (f32x8) when built with
+/// `with_lanes(capacity, 8)`. Capacity is always rounded up to the nearest
+/// multiple of the chosen lane width so the zero-padded tail past `count`
+/// is a full lane, letting the kernel load it without a scalar fallback.
+///
+/// `Debug`-derived only for test assertions like `unwrap_err()`'s panic
+/// message -- the derived output is raw pointer addresses, not buffer
+/// contents, so it's diagnostic-only and not a content comparison (there's
+/// no `PartialEq`: two buffers holding identical unit data still have
+/// different allocations and would never compare equal).
+#[derive(Debug)]
 pub struct BoidsBuffer {
     /// X positions of all units
     pub positions_x: *mut f32,
@@ -57,6 +93,39 @@ pub struct BoidsBuffer {
     /// Player IDs (units only interact with same-layer units)
     pub layers: *mut u8,
 
+    /// X component of each unit's heading unit vector, used by anisotropic
+    /// separation (see `BoidsParams::anisotropic_separation`). Zero-valued
+    /// until JS populates it, which is a safe no-op default: zero dot
+    /// products collapse the anisotropic formula back to the isotropic one.
+    pub orientation_x: *mut f32,
+    /// Y component of each unit's heading unit vector
+    pub orientation_y: *mut f32,
+    /// Per-unit length-to-width ratio (kappa) for anisotropic separation.
+    /// `1.0` (or the zeroed default, which is clamped up to `1.0`) means
+    /// circular -- isotropic separation is unchanged. Equivalent to storing
+    /// explicit major/minor contact semi-axes as a pair of arrays: combined
+    /// with `radii` (the minor semi-axis), `radii * aspect_ratio` is the
+    /// major semi-axis (see `simd::anisotropic_sigma`, which interpolates
+    /// between the two by the angle between the separation direction and
+    /// each unit's heading) -- a single ratio instead of two redundant
+    /// lengths, since only their proportion ever affects the force.
+    ///
+    /// Backlog note: the chunk2-6 request asked for this as separate
+    /// `orientations`/`radii_major`/`radii_minor` arrays. That request is
+    /// declined as superseded by this field plus `orientation_x`/`_y`
+    /// (chunk1-5) rather than implemented -- there is no separate
+    /// `radii_major`/`radii_minor` storage or distinct `orientations` array
+    /// in this codebase.
+    pub aspect_ratio: *mut f32,
+
+    /// Live per-unit override that forces a unit out of separation/cohesion/
+    /// alignment regardless of `NeighborList::valid_mask`. The precomputed
+    /// mask only reflects state as of the last rebuild, so set this (`!= 0`)
+    /// when a unit dies or starts gathering in between rebuilds -- cheaper
+    /// than rebuilding the spatial hash every time transient state changes.
+    /// Zeroed by default, which is a no-op (falls back to the precomputed bit).
+    pub separation_suppressed: *mut u8,
+
     // Output force arrays (written by SIMD computation)
     /// Separation force X components
     pub force_sep_x: *mut f32,
@@ -70,54 +139,228 @@ pub struct BoidsBuffer {
     pub force_align_x: *mut f32,
     /// Alignment force Y components
     pub force_align_y: *mut f32,
+    /// World-bounds + obstacle steering force X components
+    pub force_bound_x: *mut f32,
+    /// World-bounds + obstacle steering force Y components
+    pub force_bound_y: *mut f32,
+    /// Flow-field goal-seeking force X components
+    pub force_seek_x: *mut f32,
+    /// Flow-field goal-seeking force Y components
+    pub force_seek_y: *mut f32,
 
     /// Current number of units in buffer
     count: usize,
-    /// Allocated capacity (always multiple of 4)
+    /// Allocated capacity (always a multiple of `lanes`)
     capacity: usize,
+    /// SIMD lane width this buffer was allocated for (`4` or `8`); see `with_lanes`
+    lanes: usize,
+    /// Byte alignment every array was allocated with (`lanes * 4`); `Drop`
+    /// must reconstruct layouts with this exact alignment, not a hardcoded
+    /// constant, or freeing is UB.
+    alignment: usize,
+
+    /// Free-list bitmap for `alloc_slot`/`free_slot`: bit `i` of word `w` is
+    /// set iff slot `w * 64 + i` is occupied. Bits at or past `capacity`
+    /// (padding from rounding up to a word boundary) are always set so they
+    /// can never be handed out. Independent of `count`/`set_count`, which the
+    /// legacy dense JS-populate path still uses directly -- a caller that
+    /// never calls `alloc_slot` leaves this bitmap unused and harmless.
+    occupied: Vec<u64>,
+    /// Index of the lowest word in `occupied` known to contain a free bit.
+    /// `alloc_slot` only moves this forward, so it never rescans words it
+    /// already proved are full; `free_slot` rewinds it when it frees a slot
+    /// in an earlier word.
+    free_cursor: usize,
 }
 
 impl BoidsBuffer {
-    /// Create a new buffer with the specified capacity.
+    /// Create a new buffer with the specified capacity, using the default
+    /// (4-wide) lane width -- a thin shim over `with_lanes` for callers that
+    /// don't care about wider vectorization.
     ///
     /// Capacity is rounded up to nearest multiple of 4 for SIMD alignment.
+    ///
+    /// # Panics
+    /// Panics on capacity overflow or allocator OOM; see `try_new` for a
+    /// fallible counterpart.
     pub fn new(capacity: usize) -> Self {
-        // Round up to multiple of 4 for SIMD
-        let aligned_capacity = (capacity + 3) & !3;
+        Self::try_new(capacity).expect("BoidsBuffer allocation failed")
+    }
 
-        unsafe {
-            Self {
-                positions_x: Self::alloc_aligned(aligned_capacity),
-                positions_y: Self::alloc_aligned(aligned_capacity),
-                velocities_x: Self::alloc_aligned(aligned_capacity),
-                velocities_y: Self::alloc_aligned(aligned_capacity),
-                radii: Self::alloc_aligned(aligned_capacity),
-                states: Self::alloc_aligned_u8(aligned_capacity),
-                layers: Self::alloc_aligned_u8(aligned_capacity),
-                force_sep_x: Self::alloc_aligned(aligned_capacity),
-                force_sep_y: Self::alloc_aligned(aligned_capacity),
-                force_coh_x: Self::alloc_aligned(aligned_capacity),
-                force_coh_y: Self::alloc_aligned(aligned_capacity),
-                force_align_x: Self::alloc_aligned(aligned_capacity),
-                force_align_y: Self::alloc_aligned(aligned_capacity),
-                count: 0,
-                capacity: aligned_capacity,
-            }
+    /// Fallible counterpart to `new`. Returns `Err(BufferAllocError)` instead
+    /// of panicking/aborting if the rounded-up capacity overflows `usize`,
+    /// rejects as an `Layout`, or the allocator runs out of memory -- so a
+    /// WASM host can reject an oversized unit cap gracefully instead of
+    /// taking down the whole module.
+    pub fn try_new(capacity: usize) -> Result<Self, BufferAllocError> {
+        Self::try_with_lanes(capacity, DEFAULT_LANES)
+    }
+
+    /// Create a new buffer sized for `lanes`-wide SIMD loads (`lanes` must be
+    /// `4` or `8`: f32x4 or AVX-width f32x8). Array alignment becomes
+    /// `lanes * 4` bytes and capacity rounds up to a multiple of `lanes`
+    /// instead of the hardcoded 4, so every array's zero-padded tail past
+    /// `count` is always a full lane -- the kernel can process it with a
+    /// vector load and no scalar fallback, whichever width it's built for.
+    ///
+    /// # Panics
+    /// Panics on an unsupported lane width, capacity overflow, or allocator
+    /// OOM; see `try_with_lanes` for a fallible counterpart.
+    pub fn with_lanes(capacity: usize, lanes: usize) -> Self {
+        Self::try_with_lanes(capacity, lanes).expect("BoidsBuffer allocation failed")
+    }
+
+    /// Fallible counterpart to `with_lanes`.
+    ///
+    /// If an allocation fails partway through, every array allocated so far
+    /// is freed with its original layout before returning `Err`, so a
+    /// partial failure never leaks.
+    pub fn try_with_lanes(capacity: usize, lanes: usize) -> Result<Self, BufferAllocError> {
+        if lanes != 4 && lanes != 8 {
+            return Err(BufferAllocError::UnsupportedLaneWidth(lanes));
+        }
+        let alignment = lanes * 4;
+
+        // Round up to a multiple of `lanes`; `checked_add` catches the case
+        // where `capacity` is already within `lanes - 1` of `usize::MAX`.
+        let aligned_capacity = capacity.checked_add(lanes - 1).ok_or(BufferAllocError::CapacityOverflow)?
+            / lanes
+            * lanes;
+
+        let f32_layout = Self::f32_array_layout(aligned_capacity, alignment)?;
+        let u8_layout = Self::u8_array_layout(aligned_capacity, alignment)?;
+
+        // Every array allocated so far, with the layout it must be freed
+        // with -- unwound (in reverse is unnecessary for independent
+        // allocations, but draining all of them) if a later allocation fails.
+        let mut allocated: Vec<(*mut u8, Layout)> = Vec::with_capacity(21);
+
+        macro_rules! alloc_or_unwind {
+            ($layout:expr, $cast:ty) => {{
+                let ptr = unsafe { alloc_zeroed($layout) };
+                if ptr.is_null() {
+                    for (p, l) in allocated.drain(..) {
+                        unsafe { dealloc(p, l) };
+                    }
+                    return Err(BufferAllocError::AllocFailed);
+                }
+                allocated.push((ptr, $layout));
+                ptr as $cast
+            }};
         }
+
+        let positions_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let positions_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let velocities_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let velocities_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let radii = alloc_or_unwind!(f32_layout, *mut f32);
+        let states = alloc_or_unwind!(u8_layout, *mut u8);
+        let layers = alloc_or_unwind!(u8_layout, *mut u8);
+        let orientation_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let orientation_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let aspect_ratio = alloc_or_unwind!(f32_layout, *mut f32);
+        let separation_suppressed = alloc_or_unwind!(u8_layout, *mut u8);
+        let force_sep_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_sep_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_coh_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_coh_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_align_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_align_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_bound_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_bound_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_seek_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let force_seek_y = alloc_or_unwind!(f32_layout, *mut f32);
+
+        let mut occupied = vec![0u64; Self::bitmap_words(aligned_capacity)];
+        Self::seal_tail_bits(&mut occupied, aligned_capacity);
+
+        Ok(Self {
+            positions_x,
+            positions_y,
+            velocities_x,
+            velocities_y,
+            radii,
+            states,
+            layers,
+            orientation_x,
+            orientation_y,
+            aspect_ratio,
+            separation_suppressed,
+            force_sep_x,
+            force_sep_y,
+            force_coh_x,
+            force_coh_y,
+            force_align_x,
+            force_align_y,
+            force_bound_x,
+            force_bound_y,
+            force_seek_x,
+            force_seek_y,
+            count: 0,
+            capacity: aligned_capacity,
+            lanes,
+            alignment,
+            occupied,
+            free_cursor: 0,
+        })
+    }
+
+    /// Layout for one of the buffer's SIMD-aligned f32 arrays, folding both
+    /// a `count * 4` overflow and a rejected `Layout` into `CapacityOverflow`
+    /// (mirroring how `CollectionAllocErr` folds a `LayoutErr` into its own
+    /// `CapacityOverflow` variant)
+    fn f32_array_layout(count: usize, alignment: usize) -> Result<Layout, BufferAllocError> {
+        let bytes = count.checked_mul(4).ok_or(BufferAllocError::CapacityOverflow)?;
+        Layout::from_size_align(bytes, alignment).map_err(|_| BufferAllocError::CapacityOverflow)
     }
 
-    /// Allocate SIMD-aligned f32 array
-    unsafe fn alloc_aligned(count: usize) -> *mut f32 {
-        let layout = Layout::from_size_align(count * 4, SIMD_ALIGNMENT)
-            .expect("Invalid layout for f32 array");
-        alloc_zeroed(layout) as *mut f32
+    /// Layout for one of the buffer's SIMD-aligned u8 arrays
+    fn u8_array_layout(count: usize, alignment: usize) -> Result<Layout, BufferAllocError> {
+        Layout::from_size_align(count, alignment).map_err(|_| BufferAllocError::CapacityOverflow)
     }
 
-    /// Allocate SIMD-aligned u8 array
-    unsafe fn alloc_aligned_u8(count: usize) -> *mut u8 {
-        let layout = Layout::from_size_align(count, SIMD_ALIGNMENT)
-            .expect("Invalid layout for u8 array");
-        alloc_zeroed(layout)
+    /// Lane width this buffer was allocated for (`4` or `8`)
+    #[inline]
+    pub fn lanes(&self) -> usize {
+        self.lanes
+    }
+
+    /// Number of `u64` words needed for a slot bitmap covering `capacity` bits
+    #[inline]
+    fn bitmap_words(capacity: usize) -> usize {
+        (capacity + 63) / 64
+    }
+
+    /// Mark every bit at index `>= capacity` as occupied so `alloc_slot`
+    /// never hands out a slot past the buffer's real capacity -- padding
+    /// introduced by `occupied` covering whole 64-bit words.
+    fn seal_tail_bits(occupied: &mut [u64], capacity: usize) {
+        let last_word = capacity / 64;
+        let valid_bits = capacity % 64;
+        if last_word < occupied.len() {
+            let tail_mask = if valid_bits == 0 { 0 } else { !0u64 << valid_bits };
+            occupied[last_word] |= tail_mask;
+        }
+        for word in occupied.iter_mut().skip(last_word + 1) {
+            *word = u64::MAX;
+        }
+    }
+
+    /// Mark every bit at index `< count` as occupied, leaving `>= count`
+    /// free -- the mirror image of `seal_tail_bits`, used to rebuild the
+    /// bitmap for a range of slots known to hold live units (e.g. freshly
+    /// restored from a snapshot that doesn't itself carry the bitmap).
+    fn mark_occupied_prefix(occupied: &mut [u64], count: usize) {
+        let full_words = count / 64;
+        for word in occupied.iter_mut().take(full_words) {
+            *word = u64::MAX;
+        }
+        let rem_bits = count % 64;
+        if rem_bits > 0 {
+            if let Some(word) = occupied.get_mut(full_words) {
+                *word |= (1u64 << rem_bits) - 1;
+            }
+        }
     }
 
     /// Get current unit count
@@ -138,22 +381,260 @@ impl BoidsBuffer {
         self.capacity
     }
 
-    /// Clear all units from the buffer (resets count, keeps capacity)
+    /// Clear all units from the buffer (resets count, keeps capacity).
+    /// Also frees every slot handed out by `alloc_slot`, so the slot
+    /// allocator and the legacy dense `set_count` path agree on "empty"
+    /// either way.
     #[inline]
     pub fn clear(&mut self) {
         self.count = 0;
+        for word in self.occupied.iter_mut() {
+            *word = 0;
+        }
+        Self::seal_tail_bits(&mut self.occupied, self.capacity);
+        self.free_cursor = 0;
     }
 
     /// Set the unit count (called after JS populates the buffer)
     ///
     /// # Safety
-    /// Caller must ensure `count` does not exceed capacity.
+    /// Caller must ensure `count` does not exceed capacity -- call `reserve`
+    /// or `grow_to` first if a spawning wave might exceed it; this only
+    /// `debug_assert`s, so an unreserved call in a release build would let
+    /// `count` run past `capacity` and corrupt memory.
     #[inline]
     pub fn set_count(&mut self, count: usize) {
         debug_assert!(count <= self.capacity, "Count exceeds capacity");
         self.count = count;
     }
 
+    /// Allocate a stable slot index: the lowest free index if `free_slot` has
+    /// freed one, otherwise a fresh index that extends `count`'s high-water
+    /// mark. Unlike the legacy dense `set_count` path, an index returned here
+    /// stays valid (until `free_slot`'d) even as other units are removed, so
+    /// `NeighborList` entries, selection sets, and JS-side references never
+    /// need to be renumbered.
+    ///
+    /// Callers own populating every SoA field for the returned index; a
+    /// fresh slot starts zeroed the way the backing arrays always do, while a
+    /// reused slot keeps whatever stale data `free_slot` left behind, aside
+    /// from `states`, which `free_slot` already set to `UnitState::Dead`.
+    ///
+    /// # Panics
+    /// Panics if every slot up to `capacity()` is occupied; call
+    /// `reserve_slots` first if the caller can't bound the unit count ahead
+    /// of time.
+    pub fn alloc_slot(&mut self) -> usize {
+        while self.free_cursor < self.occupied.len() {
+            let word = self.occupied[self.free_cursor];
+            if word != u64::MAX {
+                let bit = word.trailing_ones() as usize;
+                let index = self.free_cursor * 64 + bit;
+                self.occupied[self.free_cursor] |= 1u64 << bit;
+                if index >= self.count {
+                    self.count = index + 1;
+                }
+                return index;
+            }
+            self.free_cursor += 1;
+        }
+        panic!(
+            "BoidsBuffer::alloc_slot: no free slots (capacity={}); call reserve_slots first",
+            self.capacity
+        );
+    }
+
+    /// Free a slot previously returned by `alloc_slot`, making it available
+    /// for reuse and writing `UnitState::Dead` into `states[index]` so the
+    /// SIMD/scalar kernels -- which already skip `Dead` units -- ignore it on
+    /// the very next frame without waiting for a `NeighborList` rebuild.
+    ///
+    /// # Safety
+    /// `index` must have come from `alloc_slot` on this buffer and not have
+    /// been freed since.
+    pub unsafe fn free_slot(&mut self, index: usize) {
+        debug_assert!(index < self.count, "free_slot index out of bounds");
+        let word = index / 64;
+        let bit = index % 64;
+        self.occupied[word] &= !(1u64 << bit);
+        *self.states.add(index) = UnitState::Dead as u8;
+        if word < self.free_cursor {
+            self.free_cursor = word;
+        }
+    }
+
+    /// Ensure at least `n` slots are available to `alloc_slot` without
+    /// panicking, growing the slot bitmap -- and, if `n` exceeds the current
+    /// capacity, reallocating every SoA array to a larger capacity up front,
+    /// the way a free list pre-reserves capacity instead of growing one slot
+    /// at a time. Existing slot contents and occupancy are preserved.
+    ///
+    /// # Panics
+    /// Panics on capacity overflow or allocator OOM, same as `new`.
+    pub fn reserve_slots(&mut self, n: usize) {
+        if n <= self.capacity {
+            return;
+        }
+        self.grow_to(n);
+    }
+
+    /// Ensure at least `additional` more slots than `count` are available
+    /// without reallocating again soon, doubling `capacity` instead of
+    /// growing to the exact requested size so repeated spawns amortize --
+    /// the same reasoning `Vec::reserve` uses.
+    ///
+    /// # Panics
+    /// Panics on capacity overflow or allocator OOM; see `try_reserve` for a
+    /// fallible counterpart.
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).expect("BoidsBuffer reserve failed")
+    }
+
+    /// Fallible counterpart to `reserve`.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), BufferAllocError> {
+        let needed = self.count.checked_add(additional).ok_or(BufferAllocError::CapacityOverflow)?;
+        // Compute the doubled target unconditionally against the current
+        // capacity rather than gating it behind a "do we need to grow at
+        // all" check -- `try_grow_to` already no-ops once a request stops
+        // exceeding capacity, but gating the doubling itself here would skip
+        // the amortizing growth factor entirely whenever `needed` alone
+        // happened to already fit.
+        let doubled = self.capacity.checked_mul(2).unwrap_or(usize::MAX);
+        self.try_grow_to(needed.max(doubled))
+    }
+
+    /// Reallocate every SoA array (and the slot bitmap) to at least
+    /// `requested_capacity` slots (rounded up to a multiple of `self.lanes`),
+    /// copying forward the first `self.count` live elements of each array
+    /// and zeroing the rest -- the same contents a fresh `alloc_zeroed`
+    /// buffer would have for the new slots, so SIMD tail lanes past `count`
+    /// stay clean. A no-op if `requested_capacity` doesn't exceed the
+    /// current capacity.
+    ///
+    /// # Panics
+    /// Panics on capacity overflow or allocator OOM; see `try_grow_to` for a
+    /// fallible counterpart that lets a host cap unit counts under memory
+    /// pressure instead of aborting.
+    pub fn grow_to(&mut self, requested_capacity: usize) {
+        self.try_grow_to(requested_capacity).expect("BoidsBuffer grow failed")
+    }
+
+    /// Fallible counterpart to `grow_to`. Every new array is allocated
+    /// before any old one is touched -- if an allocation fails partway
+    /// through, everything allocated so far for this grow is freed and
+    /// `self` is left exactly as it was, never partially grown.
+    pub fn try_grow_to(&mut self, requested_capacity: usize) -> Result<(), BufferAllocError> {
+        let new_capacity = requested_capacity.checked_add(self.lanes - 1).ok_or(BufferAllocError::CapacityOverflow)?
+            / self.lanes
+            * self.lanes;
+        if new_capacity <= self.capacity {
+            return Ok(());
+        }
+
+        let alignment = self.alignment;
+        let f32_layout = Self::f32_array_layout(new_capacity, alignment)?;
+        let u8_layout = Self::u8_array_layout(new_capacity, alignment)?;
+
+        // Every new array allocated so far, with the layout it must be freed
+        // with if a later allocation fails -- same unwind-on-failure shape
+        // as `try_with_lanes`.
+        let mut allocated: Vec<(*mut u8, Layout)> = Vec::with_capacity(21);
+
+        macro_rules! alloc_or_unwind {
+            ($layout:expr, $cast:ty) => {{
+                let ptr = unsafe { alloc_zeroed($layout) };
+                if ptr.is_null() {
+                    for (p, l) in allocated.drain(..) {
+                        unsafe { dealloc(p, l) };
+                    }
+                    return Err(BufferAllocError::AllocFailed);
+                }
+                allocated.push((ptr, $layout));
+                ptr as $cast
+            }};
+        }
+
+        let new_positions_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_positions_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_velocities_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_velocities_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_radii = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_states = alloc_or_unwind!(u8_layout, *mut u8);
+        let new_layers = alloc_or_unwind!(u8_layout, *mut u8);
+        let new_orientation_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_orientation_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_aspect_ratio = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_separation_suppressed = alloc_or_unwind!(u8_layout, *mut u8);
+        let new_force_sep_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_sep_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_coh_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_coh_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_align_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_align_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_bound_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_bound_y = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_seek_x = alloc_or_unwind!(f32_layout, *mut f32);
+        let new_force_seek_y = alloc_or_unwind!(f32_layout, *mut f32);
+
+        // All 21 new arrays allocated; now copy forward the live data and
+        // free the old arrays one field at a time.
+        let old_capacity = self.capacity;
+        macro_rules! commit_f32_field {
+            ($field:ident, $new:ident) => {{
+                unsafe {
+                    std::ptr::copy_nonoverlapping(self.$field, $new, self.count);
+                    let old_layout = Layout::from_size_align(old_capacity * 4, alignment).unwrap();
+                    dealloc(self.$field as *mut u8, old_layout);
+                }
+                self.$field = $new;
+            }};
+        }
+        macro_rules! commit_u8_field {
+            ($field:ident, $new:ident) => {{
+                unsafe {
+                    std::ptr::copy_nonoverlapping(self.$field, $new, self.count);
+                    let old_layout = Layout::from_size_align(old_capacity, alignment).unwrap();
+                    dealloc(self.$field, old_layout);
+                }
+                self.$field = $new;
+            }};
+        }
+
+        commit_f32_field!(positions_x, new_positions_x);
+        commit_f32_field!(positions_y, new_positions_y);
+        commit_f32_field!(velocities_x, new_velocities_x);
+        commit_f32_field!(velocities_y, new_velocities_y);
+        commit_f32_field!(radii, new_radii);
+        commit_u8_field!(states, new_states);
+        commit_u8_field!(layers, new_layers);
+        commit_f32_field!(orientation_x, new_orientation_x);
+        commit_f32_field!(orientation_y, new_orientation_y);
+        commit_f32_field!(aspect_ratio, new_aspect_ratio);
+        commit_u8_field!(separation_suppressed, new_separation_suppressed);
+        commit_f32_field!(force_sep_x, new_force_sep_x);
+        commit_f32_field!(force_sep_y, new_force_sep_y);
+        commit_f32_field!(force_coh_x, new_force_coh_x);
+        commit_f32_field!(force_coh_y, new_force_coh_y);
+        commit_f32_field!(force_align_x, new_force_align_x);
+        commit_f32_field!(force_align_y, new_force_align_y);
+        commit_f32_field!(force_bound_x, new_force_bound_x);
+        commit_f32_field!(force_bound_y, new_force_bound_y);
+        commit_f32_field!(force_seek_x, new_force_seek_x);
+        commit_f32_field!(force_seek_y, new_force_seek_y);
+
+        // The old tail padding bits were sealed as occupied; they now name
+        // real slots, so clear them before sealing the new tail.
+        let old_valid_bits = old_capacity % 64;
+        if old_valid_bits != 0 {
+            self.occupied[old_capacity / 64] &= (1u64 << old_valid_bits) - 1;
+            self.free_cursor = self.free_cursor.min(old_capacity / 64);
+        }
+        self.capacity = new_capacity;
+        self.occupied.resize(Self::bitmap_words(new_capacity), 0);
+        Self::seal_tail_bits(&mut self.occupied, new_capacity);
+        Ok(())
+    }
+
     /// Get raw pointer to positions_x for JS interop
     #[inline]
     pub fn positions_x_ptr(&self) -> *mut f32 {
@@ -196,6 +677,30 @@ impl BoidsBuffer {
         self.layers
     }
 
+    /// Get raw pointer to orientation_x for JS interop
+    #[inline]
+    pub fn orientation_x_ptr(&self) -> *mut f32 {
+        self.orientation_x
+    }
+
+    /// Get raw pointer to orientation_y for JS interop
+    #[inline]
+    pub fn orientation_y_ptr(&self) -> *mut f32 {
+        self.orientation_y
+    }
+
+    /// Get raw pointer to aspect_ratio for JS interop
+    #[inline]
+    pub fn aspect_ratio_ptr(&self) -> *mut f32 {
+        self.aspect_ratio
+    }
+
+    /// Get raw pointer to separation_suppressed for JS interop
+    #[inline]
+    pub fn separation_suppressed_ptr(&self) -> *mut u8 {
+        self.separation_suppressed
+    }
+
     /// Get raw pointer to separation force X for JS interop
     #[inline]
     pub fn force_sep_x_ptr(&self) -> *mut f32 {
@@ -232,6 +737,30 @@ impl BoidsBuffer {
         self.force_align_y
     }
 
+    /// Get raw pointer to world-bounds/obstacle steering force X for JS interop
+    #[inline]
+    pub fn force_bound_x_ptr(&self) -> *mut f32 {
+        self.force_bound_x
+    }
+
+    /// Get raw pointer to world-bounds/obstacle steering force Y for JS interop
+    #[inline]
+    pub fn force_bound_y_ptr(&self) -> *mut f32 {
+        self.force_bound_y
+    }
+
+    /// Get raw pointer to flow-field seek force X for JS interop
+    #[inline]
+    pub fn force_seek_x_ptr(&self) -> *mut f32 {
+        self.force_seek_x
+    }
+
+    /// Get raw pointer to flow-field seek force Y for JS interop
+    #[inline]
+    pub fn force_seek_y_ptr(&self) -> *mut f32 {
+        self.force_seek_y
+    }
+
     /// Zero all output force arrays
     pub fn zero_forces(&mut self) {
         unsafe {
@@ -241,6 +770,10 @@ impl BoidsBuffer {
             std::ptr::write_bytes(self.force_coh_y, 0, self.capacity);
             std::ptr::write_bytes(self.force_align_x, 0, self.capacity);
             std::ptr::write_bytes(self.force_align_y, 0, self.capacity);
+            std::ptr::write_bytes(self.force_bound_x, 0, self.capacity);
+            std::ptr::write_bytes(self.force_bound_y, 0, self.capacity);
+            std::ptr::write_bytes(self.force_seek_x, 0, self.capacity);
+            std::ptr::write_bytes(self.force_seek_y, 0, self.capacity);
         }
     }
 
@@ -278,13 +811,214 @@ impl BoidsBuffer {
         debug_assert!(index < self.count, "Index out of bounds");
         (*self.force_align_x.add(index), *self.force_align_y.add(index))
     }
+
+    /// Read world-bounds/obstacle steering force at index
+    #[inline]
+    pub unsafe fn get_boundary_force(&self, index: usize) -> (f32, f32) {
+        debug_assert!(index < self.count, "Index out of bounds");
+        (*self.force_bound_x.add(index), *self.force_bound_y.add(index))
+    }
+
+    /// Read flow-field goal-seeking force at index
+    #[inline]
+    pub unsafe fn get_seek_force(&self, index: usize) -> (f32, f32) {
+        debug_assert!(index < self.count, "Index out of bounds");
+        (*self.force_seek_x.add(index), *self.force_seek_y.add(index))
+    }
+
+    /// Serialize the persistent simulation state (positions, velocities,
+    /// radii, states, layers, orientation, aspect ratio, count/capacity)
+    /// into a flat, versioned, little-endian byte buffer for save/replay or
+    /// lockstep networking.
+    ///
+    /// Output force arrays aren't included -- they're transient, fully
+    /// re-derived by the next `compute_all_forces`/`compute_boundary_forces`
+    /// call, and would just bloat the snapshot. Every field is written
+    /// through explicit `to_le_bytes` rather than a pointer cast, so the
+    /// on-disk layout never assumes more than 1-byte alignment and is stable
+    /// across host endianness (see `deserialize`).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(SNAPSHOT_HEADER_LEN + self.capacity * SNAPSHOT_BYTES_PER_UNIT);
+
+        out.extend_from_slice(SNAPSHOT_MAGIC);
+        out.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        out.push(SNAPSHOT_ENDIAN_LITTLE);
+        out.push(0); // reserved, for future header fields
+        out.extend_from_slice(&(self.count as u32).to_le_bytes());
+        out.extend_from_slice(&(self.capacity as u32).to_le_bytes());
+
+        unsafe {
+            write_f32_array(&mut out, self.positions_x, self.capacity);
+            write_f32_array(&mut out, self.positions_y, self.capacity);
+            write_f32_array(&mut out, self.velocities_x, self.capacity);
+            write_f32_array(&mut out, self.velocities_y, self.capacity);
+            write_f32_array(&mut out, self.radii, self.capacity);
+            write_f32_array(&mut out, self.orientation_x, self.capacity);
+            write_f32_array(&mut out, self.orientation_y, self.capacity);
+            write_f32_array(&mut out, self.aspect_ratio, self.capacity);
+
+            out.extend_from_slice(std::slice::from_raw_parts(self.states, self.capacity));
+            out.extend_from_slice(std::slice::from_raw_parts(self.layers, self.capacity));
+            out.extend_from_slice(std::slice::from_raw_parts(self.separation_suppressed, self.capacity));
+        }
+
+        out
+    }
+
+    /// Reconstruct a `BoidsBuffer` from bytes produced by `serialize`.
+    ///
+    /// Validates the magic/version/endianness header and that `bytes` is
+    /// exactly as long as the header's declared `capacity` demands, and that
+    /// `count <= capacity`, before allocating or writing anything -- a
+    /// truncated or hostile buffer is rejected outright rather than read out
+    /// of bounds.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < SNAPSHOT_HEADER_LEN {
+            return Err(SnapshotError::Truncated);
+        }
+        if &bytes[0..4] != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let endian = bytes[6];
+        if endian != SNAPSHOT_ENDIAN_LITTLE {
+            return Err(SnapshotError::UnsupportedEndian(endian));
+        }
+
+        let count = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let capacity = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        if count > capacity {
+            return Err(SnapshotError::CountExceedsCapacity { count, capacity });
+        }
+
+        let expected_len = SNAPSHOT_HEADER_LEN + capacity * SNAPSHOT_BYTES_PER_UNIT;
+        if bytes.len() != expected_len {
+            return Err(SnapshotError::Truncated);
+        }
+
+        let mut buffer = Self::new(capacity);
+        // `Self::new` rounds its argument up to a multiple of 4, so a
+        // `capacity` that wasn't already aligned (only possible if the bytes
+        // didn't actually come from `serialize`) would silently allocate
+        // larger arrays than the header's capacity; reject that up front.
+        if buffer.capacity != capacity {
+            return Err(SnapshotError::CapacityNotAligned(capacity));
+        }
+        buffer.count = count;
+        // `serialize` doesn't persist the occupied bitmap -- every live unit
+        // sits in `0..count` by construction, the same invariant `alloc_slot`
+        // maintains -- so rebuild it here. Leaving it untouched (all-free,
+        // as `Self::new` initializes it) would let `alloc_slot` hand out a
+        // still-live restored unit's index as a "free" slot.
+        Self::mark_occupied_prefix(&mut buffer.occupied, count);
+        buffer.free_cursor = count / 64;
+
+        let mut offset = SNAPSHOT_HEADER_LEN;
+        unsafe {
+            offset = read_f32_array(bytes, offset, buffer.positions_x, capacity);
+            offset = read_f32_array(bytes, offset, buffer.positions_y, capacity);
+            offset = read_f32_array(bytes, offset, buffer.velocities_x, capacity);
+            offset = read_f32_array(bytes, offset, buffer.velocities_y, capacity);
+            offset = read_f32_array(bytes, offset, buffer.radii, capacity);
+            offset = read_f32_array(bytes, offset, buffer.orientation_x, capacity);
+            offset = read_f32_array(bytes, offset, buffer.orientation_y, capacity);
+            offset = read_f32_array(bytes, offset, buffer.aspect_ratio, capacity);
+
+            std::ptr::copy_nonoverlapping(bytes[offset..offset + capacity].as_ptr(), buffer.states, capacity);
+            offset += capacity;
+            std::ptr::copy_nonoverlapping(bytes[offset..offset + capacity].as_ptr(), buffer.layers, capacity);
+            offset += capacity;
+            std::ptr::copy_nonoverlapping(
+                bytes[offset..offset + capacity].as_ptr(),
+                buffer.separation_suppressed,
+                capacity,
+            );
+        }
+
+        Ok(buffer)
+    }
 }
 
+/// Magic bytes identifying a `BoidsBuffer` snapshot
+const SNAPSHOT_MAGIC: &[u8; 4] = b"BDSB";
+/// Snapshot format version; bump when the header or field layout changes
+const SNAPSHOT_VERSION: u16 = 1;
+/// Only little-endian snapshots are currently produced or accepted
+const SNAPSHOT_ENDIAN_LITTLE: u8 = 0;
+/// `magic(4) + version(2) + endian(1) + reserved(1) + count(4) + capacity(4)`
+const SNAPSHOT_HEADER_LEN: usize = 16;
+/// Bytes of body per unit of capacity: 8 f32 arrays + 3 u8 arrays
+const SNAPSHOT_BYTES_PER_UNIT: usize = 8 * 4 + 3;
+
+/// Append `len` elements of `ptr` to `out` as explicit little-endian bytes,
+/// one `to_le_bytes` at a time rather than a pointer-cast bulk copy, so the
+/// on-disk layout never depends on host endianness or alignment.
+unsafe fn write_f32_array(out: &mut Vec<u8>, ptr: *const f32, len: usize) {
+    for i in 0..len {
+        out.extend_from_slice(&(*ptr.add(i)).to_le_bytes());
+    }
+}
+
+/// Read `len` little-endian f32s starting at `bytes[offset]` via unaligned
+/// `from_le_bytes` chunks and write them into `dst`, returning the offset
+/// just past the read region.
+unsafe fn read_f32_array(bytes: &[u8], offset: usize, dst: *mut f32, len: usize) -> usize {
+    for i in 0..len {
+        let base = offset + i * 4;
+        let value = f32::from_le_bytes(bytes[base..base + 4].try_into().unwrap());
+        *dst.add(i) = value;
+    }
+    offset + len * 4
+}
+
+/// Errors from `BoidsBuffer::deserialize`
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// Buffer is shorter than the fixed header, or doesn't match the body
+    /// length implied by the header's `capacity`
+    Truncated,
+    /// First 4 bytes don't match `SNAPSHOT_MAGIC`
+    BadMagic,
+    /// Header version isn't one this build knows how to read
+    UnsupportedVersion(u16),
+    /// Header endianness tag isn't `SNAPSHOT_ENDIAN_LITTLE`
+    UnsupportedEndian(u8),
+    /// Header `count` is larger than its own `capacity`
+    CountExceedsCapacity { count: usize, capacity: usize },
+    /// Header `capacity` isn't a multiple of 4, so it couldn't have come
+    /// from a real `BoidsBuffer` (see `BoidsBuffer::new`)
+    CapacityNotAligned(usize),
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Truncated => write!(f, "snapshot buffer is truncated or the wrong length"),
+            SnapshotError::BadMagic => write!(f, "snapshot buffer has the wrong magic bytes"),
+            SnapshotError::UnsupportedVersion(v) => write!(f, "unsupported snapshot version {v}"),
+            SnapshotError::UnsupportedEndian(e) => write!(f, "unsupported snapshot endianness tag {e}"),
+            SnapshotError::CountExceedsCapacity { count, capacity } => {
+                write!(f, "snapshot count {count} exceeds its own capacity {capacity}")
+            }
+            SnapshotError::CapacityNotAligned(capacity) => {
+                write!(f, "snapshot capacity {capacity} is not a multiple of 4")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
 impl Drop for BoidsBuffer {
     fn drop(&mut self) {
         unsafe {
-            let f32_layout = Layout::from_size_align(self.capacity * 4, SIMD_ALIGNMENT).unwrap();
-            let u8_layout = Layout::from_size_align(self.capacity, SIMD_ALIGNMENT).unwrap();
+            let f32_layout = Layout::from_size_align(self.capacity * 4, self.alignment).unwrap();
+            let u8_layout = Layout::from_size_align(self.capacity, self.alignment).unwrap();
 
             dealloc(self.positions_x as *mut u8, f32_layout);
             dealloc(self.positions_y as *mut u8, f32_layout);
@@ -293,16 +1027,47 @@ impl Drop for BoidsBuffer {
             dealloc(self.radii as *mut u8, f32_layout);
             dealloc(self.states, u8_layout);
             dealloc(self.layers, u8_layout);
+            dealloc(self.orientation_x as *mut u8, f32_layout);
+            dealloc(self.orientation_y as *mut u8, f32_layout);
+            dealloc(self.aspect_ratio as *mut u8, f32_layout);
+            dealloc(self.separation_suppressed, u8_layout);
             dealloc(self.force_sep_x as *mut u8, f32_layout);
             dealloc(self.force_sep_y as *mut u8, f32_layout);
             dealloc(self.force_coh_x as *mut u8, f32_layout);
             dealloc(self.force_coh_y as *mut u8, f32_layout);
             dealloc(self.force_align_x as *mut u8, f32_layout);
             dealloc(self.force_align_y as *mut u8, f32_layout);
+            dealloc(self.force_bound_x as *mut u8, f32_layout);
+            dealloc(self.force_bound_y as *mut u8, f32_layout);
+            dealloc(self.force_seek_x as *mut u8, f32_layout);
+            dealloc(self.force_seek_y as *mut u8, f32_layout);
         }
     }
 }
 
+/// Returned by `try_add_neighbor`/`try_add_neighbor_with_validity` when
+/// `unit_index` already has `max_neighbors_per_unit` neighbors and the caller
+/// hasn't opted into eviction via `add_neighbor_nearest_with_validity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeighborOverflow {
+    /// Unit that was already at `max_neighbors_per_unit`
+    pub unit_index: usize,
+    /// Neighbor that was dropped instead of stored
+    pub neighbor_index: u32,
+}
+
+impl std::fmt::Display for NeighborOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unit {} already has the max neighbors per unit, dropped neighbor {}",
+            self.unit_index, self.neighbor_index
+        )
+    }
+}
+
+impl std::error::Error for NeighborOverflow {}
+
 /// Neighbor list for spatial queries
 ///
 /// Stores indices of nearby units for each unit. This enables batch
@@ -310,12 +1075,40 @@ impl Drop for BoidsBuffer {
 pub struct NeighborList {
     /// Flat array of neighbor indices
     neighbors: Vec<u32>,
+    /// Precomputed "eligible for force computation" bit for each entry in
+    /// `neighbors` (`-1` = all bits set, `0` = none), parallel to it one for
+    /// one. Captures the same self/dead/layer/worker-worker/gathering rules
+    /// `simd::compute_unit_forces_simd` used to re-derive from
+    /// `is_valid_neighbor` every frame, computed once when the list is
+    /// (re)built instead. Stored pre-expanded to `-1`/`0` so a batch of 4 can
+    /// be turned into a lane mask with one `v128_load` rather than 4 branchy
+    /// calls. Because a neighbor can die or start gathering between
+    /// rebuilds, this can go stale -- see `BoidsBuffer::separation_suppressed`
+    /// for the live override that still catches those without a full
+    /// spatial rebuild.
+    valid_mask: Vec<i32>,
     /// Start index in neighbors array for each unit
     offsets: Vec<u32>,
     /// Number of neighbors for each unit
     counts: Vec<u32>,
     /// Capacity (max units)
     capacity: usize,
+    /// Whether this list only stores `j` in `i`'s list when `i < j` (see
+    /// `build_from_grid_half`); callers must use the matching half-list-aware
+    /// force kernel instead of the regular full-list one
+    half: bool,
+    /// Upper bound on neighbors stored per unit (`usize::MAX` = unbounded).
+    /// Enforced by `try_add_neighbor*` (drop policy) and
+    /// `add_neighbor_nearest_with_validity` (evict-the-farthest policy);
+    /// plain `add_neighbor`/`add_neighbor_with_validity` ignore it, so a
+    /// dense clump fed through those still grows unbounded -- see
+    /// `set_max_neighbors_per_unit`.
+    max_neighbors_per_unit: usize,
+    /// Squared distances parallel to the *current* unit's slice of
+    /// `neighbors` (reset in `begin_unit`), used only by
+    /// `add_neighbor_nearest_with_validity` to find the farthest entry to
+    /// evict. Scratch state, not part of the list's public shape.
+    current_unit_distances: Vec<f32>,
 }
 
 impl NeighborList {
@@ -326,15 +1119,52 @@ impl NeighborList {
 
         Self {
             neighbors: Vec::with_capacity(neighbor_capacity),
+            valid_mask: Vec::with_capacity(neighbor_capacity),
             offsets: vec![0; max_units],
             counts: vec![0; max_units],
             capacity: max_units,
+            half: false,
+            max_neighbors_per_unit: usize::MAX,
+            current_unit_distances: Vec::new(),
         }
     }
 
+    /// Set the upper bound on neighbors stored per unit. Callers that want
+    /// the list to converge to the k-nearest under dense clumps should pair
+    /// this with `add_neighbor_nearest_with_validity`; callers that just want
+    /// to cap memory and drop the rest should pair it with
+    /// `try_add_neighbor`/`try_add_neighbor_with_validity`.
+    #[inline]
+    pub fn set_max_neighbors_per_unit(&mut self, max: usize) {
+        self.max_neighbors_per_unit = max;
+    }
+
+    /// Current per-unit neighbor cap (`usize::MAX` if unbounded)
+    #[inline]
+    pub fn max_neighbors_per_unit(&self) -> usize {
+        self.max_neighbors_per_unit
+    }
+
+    /// Pre-grow the flat neighbor/valid-mask arrays to hold at least
+    /// `expected_total` entries, the way a freelist reserves space up front
+    /// instead of letting a dense clump reallocate mid-frame.
+    pub fn reserve(&mut self, expected_total: usize) {
+        let additional = expected_total.saturating_sub(self.neighbors.len());
+        self.neighbors.reserve(additional);
+        self.valid_mask.reserve(additional);
+    }
+
+    /// Whether this list was built with `build_from_grid_half` (only
+    /// contains `i < j` pairs, so it needs the half-list-aware force kernel)
+    #[inline]
+    pub fn is_half(&self) -> bool {
+        self.half
+    }
+
     /// Clear the neighbor list for reuse
     pub fn clear(&mut self) {
         self.neighbors.clear();
+        self.valid_mask.clear();
         // Counts will be overwritten, no need to zero
     }
 
@@ -344,15 +1174,87 @@ impl NeighborList {
         debug_assert!(unit_index < self.capacity, "Unit index out of bounds");
         self.offsets[unit_index] = self.neighbors.len() as u32;
         self.counts[unit_index] = 0;
+        self.current_unit_distances.clear();
     }
 
-    /// Add a neighbor to the current unit
+    /// Add a neighbor to the current unit, eligible for force computation by
+    /// default (see `add_neighbor_with_validity` to precompute the bit
+    /// instead). Ignores `max_neighbors_per_unit`; see `try_add_neighbor` or
+    /// `add_neighbor_nearest_with_validity` to enforce the cap.
     #[inline]
     pub fn add_neighbor(&mut self, unit_index: usize, neighbor_index: u32) {
+        self.add_neighbor_with_validity(unit_index, neighbor_index, true);
+    }
+
+    /// Add a neighbor along with its precomputed eligibility bit (see
+    /// `valid_mask`). Ignores `max_neighbors_per_unit`; see
+    /// `try_add_neighbor_with_validity` or `add_neighbor_nearest_with_validity`
+    /// to enforce the cap.
+    #[inline]
+    pub fn add_neighbor_with_validity(&mut self, unit_index: usize, neighbor_index: u32, valid: bool) {
         self.neighbors.push(neighbor_index);
+        self.valid_mask.push(if valid { -1 } else { 0 });
         self.counts[unit_index] += 1;
     }
 
+    /// Like `add_neighbor`, but rejects the neighbor instead of pushing it
+    /// once `unit_index` already has `max_neighbors_per_unit` entries.
+    #[inline]
+    pub fn try_add_neighbor(&mut self, unit_index: usize, neighbor_index: u32) -> Result<(), NeighborOverflow> {
+        self.try_add_neighbor_with_validity(unit_index, neighbor_index, true)
+    }
+
+    /// Like `add_neighbor_with_validity`, but rejects the neighbor instead of
+    /// pushing it once `unit_index` already has `max_neighbors_per_unit`
+    /// entries -- the "drop the new one" overflow policy.
+    pub fn try_add_neighbor_with_validity(
+        &mut self,
+        unit_index: usize,
+        neighbor_index: u32,
+        valid: bool,
+    ) -> Result<(), NeighborOverflow> {
+        if self.counts[unit_index] as usize >= self.max_neighbors_per_unit {
+            return Err(NeighborOverflow { unit_index, neighbor_index });
+        }
+        self.add_neighbor_with_validity(unit_index, neighbor_index, valid);
+        Ok(())
+    }
+
+    /// The "evict the farthest" overflow policy: while `unit_index` has room
+    /// under `max_neighbors_per_unit`, this just adds the neighbor like
+    /// `add_neighbor_with_validity`. Once full, it replaces the current
+    /// farthest neighbor (by `distance_sq`) with the new one only if the new
+    /// one is closer, so the list converges to the unit's k-nearest instead
+    /// of whichever `k` happened to be seen first.
+    ///
+    /// `distance_sq` must be the squared distance from `unit_index` to
+    /// `neighbor_index`; callers must call this for every neighbor of a unit
+    /// between its `begin_unit` and the next one (mixing in a plain
+    /// `add_neighbor*` call resets nothing but desyncs the distance scratch
+    /// from the neighbor list, so eviction picks the wrong entry).
+    pub fn add_neighbor_nearest_with_validity(&mut self, unit_index: usize, neighbor_index: u32, valid: bool, distance_sq: f32) {
+        if (self.counts[unit_index] as usize) < self.max_neighbors_per_unit {
+            self.add_neighbor_with_validity(unit_index, neighbor_index, valid);
+            self.current_unit_distances.push(distance_sq);
+            return;
+        }
+
+        let farthest = self
+            .current_unit_distances
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let Some((slot, &farthest_dist)) = farthest {
+            if distance_sq < farthest_dist {
+                let offset = self.offsets[unit_index] as usize;
+                self.neighbors[offset + slot] = neighbor_index;
+                self.valid_mask[offset + slot] = if valid { -1 } else { 0 };
+                self.current_unit_distances[slot] = distance_sq;
+            }
+        }
+    }
+
     /// Get neighbors for a unit
     #[inline]
     pub fn get_neighbors(&self, unit_index: usize) -> &[u32] {
@@ -361,6 +1263,15 @@ impl NeighborList {
         &self.neighbors[offset..offset + count]
     }
 
+    /// Get the precomputed validity mask for a unit's neighbors, parallel to
+    /// `get_neighbors` (same offset/count, one `-1`/`0` entry per neighbor)
+    #[inline]
+    pub fn get_valid_mask(&self, unit_index: usize) -> &[i32] {
+        let offset = self.offsets[unit_index] as usize;
+        let count = self.counts[unit_index] as usize;
+        &self.valid_mask[offset..offset + count]
+    }
+
     /// Get neighbor count for a unit
     #[inline]
     pub fn neighbor_count(&self, unit_index: usize) -> usize {
@@ -390,7 +1301,7 @@ impl NeighborList {
     pub fn neighbors_ptr_mut(&mut self) -> *mut u32 {
         // Ensure capacity
         if self.neighbors.capacity() == 0 {
-            self.neighbors.reserve(self.capacity * 8);
+            self.reserve(self.capacity * 8);
         }
         self.neighbors.as_mut_ptr()
     }
@@ -408,66 +1319,1212 @@ impl NeighborList {
     }
 
     /// Set the number of neighbors (after JS populates the array)
+    ///
+    /// This legacy JS-side broad-phase path can't know per-pair eligibility,
+    /// so it fills `valid_mask` as all-valid; prefer `build_from_grid`, whose
+    /// precomputed bits are the point of this optimization.
+    ///
+    /// # Panics
+    /// Panics if `count` exceeds the capacity already reserved via
+    /// `reserve`/`neighbors_ptr_mut` -- `set_len` past that is UB, so this
+    /// validates it instead of trusting JS blindly.
     #[inline]
     pub fn set_neighbor_count(&mut self, count: usize) {
-        // SAFETY: JS has written `count` neighbors
+        assert!(count <= self.neighbors.capacity(), "Neighbor count exceeds reserved capacity");
         unsafe {
             self.neighbors.set_len(count);
         }
+        self.valid_mask.resize(count, -1);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Rebuild the neighbor list internally from `buffer` using a uniform
+    /// spatial hash grid, so JS no longer has to run its own broad-phase.
+    ///
+    /// `cell_size` should be at least as large as the furthest interaction
+    /// radius (see `BoidsParams::max_interaction_radius`) so that scanning
+    /// the 3x3 block of cells around a unit is guaranteed to find every unit
+    /// that could actually interact with it.
+    pub fn build_from_grid(&mut self, buffer: &BoidsBuffer, cell_size: f32) {
+        self.clear();
+        self.half = false;
 
-    #[test]
-    fn test_buffer_creation() {
-        let buffer = BoidsBuffer::new(100);
-        assert_eq!(buffer.capacity(), 100);
-        assert_eq!(buffer.len(), 0);
-        assert!(buffer.is_empty());
-    }
+        let count = buffer.len();
+        if count == 0 {
+            return;
+        }
 
-    #[test]
-    fn test_buffer_alignment() {
-        let buffer = BoidsBuffer::new(100);
-        // Check that pointers are 16-byte aligned
-        assert_eq!(buffer.positions_x as usize % 16, 0);
-        assert_eq!(buffer.positions_y as usize % 16, 0);
-        assert_eq!(buffer.velocities_x as usize % 16, 0);
-        assert_eq!(buffer.velocities_y as usize % 16, 0);
-    }
+        let grid = UniformGrid::build(buffer, cell_size.max(0.0001));
 
-    #[test]
-    fn test_capacity_rounding() {
-        // Capacity should round up to multiple of 4
-        let buffer = BoidsBuffer::new(1);
-        assert_eq!(buffer.capacity(), 4);
+        unsafe {
+            for unit_idx in 0..count {
+                self.begin_unit(unit_idx);
 
-        let buffer = BoidsBuffer::new(5);
-        assert_eq!(buffer.capacity(), 8);
+                let state = *buffer.states.add(unit_idx);
+                if state == UnitState::Dead as u8 {
+                    continue;
+                }
 
-        let buffer = BoidsBuffer::new(8);
-        assert_eq!(buffer.capacity(), 8);
+                let layer = *buffer.layers.add(unit_idx);
+                let x = *buffer.positions_x.add(unit_idx);
+                let y = *buffer.positions_y.add(unit_idx);
+                let (cx, cy) = grid.cell_of(x, y);
+
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        for candidate in grid.bucket(cx + dx, cy + dy) {
+                            let candidate = candidate as usize;
+                            if candidate == unit_idx {
+                                continue;
+                            }
+
+                            // Dead units are never inserted into the grid, but
+                            // cross-layer units (e.g. flyers vs ground) are.
+                            let candidate_layer = *buffer.layers.add(candidate);
+                            if candidate_layer != layer {
+                                continue;
+                            }
+
+                            let candidate_state = *buffer.states.add(candidate);
+                            let valid = neighbor_pair_valid(state, candidate_state);
+                            let cand_x = *buffer.positions_x.add(candidate);
+                            let cand_y = *buffer.positions_y.add(candidate);
+                            let dist_sq = (cand_x - x) * (cand_x - x) + (cand_y - y) * (cand_y - y);
+                            self.add_neighbor_nearest_with_validity(unit_idx, candidate as u32, valid, dist_sq);
+                        }
+                    }
+                }
+            }
+        }
     }
 
-    #[test]
-    fn test_neighbor_list() {
-        let mut list = NeighborList::new(10);
+    /// Same broad-phase as `build_from_grid`, but only stores `j` in `i`'s
+    /// list when `i < j`, so each interacting pair appears exactly once
+    /// across the whole list. Pair with `scalar::compute_all_forces_half`,
+    /// which reuses that single evaluation for both units via Newton's
+    /// third law instead of recomputing it from both sides.
+    pub fn build_from_grid_half(&mut self, buffer: &BoidsBuffer, cell_size: f32) {
+        self.clear();
+        self.half = true;
 
-        list.begin_unit(0);
-        list.add_neighbor(0, 1);
-        list.add_neighbor(0, 2);
-        list.add_neighbor(0, 3);
+        let count = buffer.len();
+        if count == 0 {
+            return;
+        }
 
-        list.begin_unit(1);
-        list.add_neighbor(1, 0);
-        list.add_neighbor(1, 2);
+        let grid = UniformGrid::build(buffer, cell_size.max(0.0001));
 
-        assert_eq!(list.neighbor_count(0), 3);
-        assert_eq!(list.neighbor_count(1), 2);
-        assert_eq!(list.get_neighbors(0), &[1, 2, 3]);
-        assert_eq!(list.get_neighbors(1), &[0, 2]);
+        unsafe {
+            for unit_idx in 0..count {
+                self.begin_unit(unit_idx);
+
+                let state = *buffer.states.add(unit_idx);
+                if state == UnitState::Dead as u8 {
+                    continue;
+                }
+
+                let layer = *buffer.layers.add(unit_idx);
+                let x = *buffer.positions_x.add(unit_idx);
+                let y = *buffer.positions_y.add(unit_idx);
+                let (cx, cy) = grid.cell_of(x, y);
+
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        for candidate in grid.bucket(cx + dx, cy + dy) {
+                            let candidate = candidate as usize;
+                            if candidate <= unit_idx {
+                                continue;
+                            }
+
+                            let candidate_layer = *buffer.layers.add(candidate);
+                            if candidate_layer != layer {
+                                continue;
+                            }
+
+                            let candidate_state = *buffer.states.add(candidate);
+                            let valid = neighbor_pair_valid(state, candidate_state);
+                            let cand_x = *buffer.positions_x.add(candidate);
+                            let cand_y = *buffer.positions_y.add(candidate);
+                            let dist_sq = (cand_x - x) * (cand_x - x) + (cand_y - y) * (cand_y - y);
+                            self.add_neighbor_nearest_with_validity(unit_idx, candidate as u32, valid, dist_sq);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a unit pair is eligible for force computation: neither is dead,
+/// and it isn't a worker-worker pair or one where the neighbor is gathering
+/// (same rules `simd::is_valid_neighbor` applies live; self and layer are
+/// already guaranteed by the grid walk above, so they aren't rechecked here).
+/// Computed once per pair when the list is built -- see `NeighborList::valid_mask`.
+#[inline]
+fn neighbor_pair_valid(unit_state: u8, candidate_state: u8) -> bool {
+    if candidate_state == UnitState::Dead as u8 {
+        return false;
+    }
+
+    if unit_state == UnitState::Worker as u8 && candidate_state == UnitState::Worker as u8 {
+        return false;
+    }
+
+    if candidate_state == UnitState::Gathering as u8 {
+        return false;
+    }
+
+    true
+}
+
+/// Fixed cluster size for the cluster-based SIMD kernel (see
+/// `simd::compute_all_forces_simd_clustered`). `BoidsBuffer` capacity is
+/// always rounded up to a multiple of this, so every cluster's members are
+/// contiguous and 16-byte aligned in the SoA arrays -- a whole cluster can
+/// be loaded with a single `v128_load` instead of 4 scattered
+/// `gather_f32x4` calls.
+pub const CLUSTER_SIZE: usize = 4;
+
+/// Cluster-to-cluster neighbor list for the cluster-based SIMD kernel.
+///
+/// Unlike `NeighborList` (unit -> unit), this groups units into fixed
+/// `CLUSTER_SIZE`-wide index blocks ("clusters": cluster `k` is units
+/// `[k * CLUSTER_SIZE, (k + 1) * CLUSTER_SIZE)`) and stores, for each
+/// cluster, the other clusters containing at least one unit within
+/// interaction range of at least one of its members. Clusters are index
+/// blocks rather than a spatial re-sort, so the existing unit-index
+/// contract the rest of the engine depends on (JS interop, the wasi ABI)
+/// doesn't need to change; locality instead comes from loading a whole
+/// cluster at once, which amortizes one aligned load across up to
+/// `CLUSTER_SIZE` times as many pairwise tests as a scattered per-neighbor
+/// gather would.
+pub struct ClusterNeighborList {
+    /// Flat array of neighbor cluster indices
+    neighbors: Vec<u32>,
+    /// Start index in `neighbors` for each cluster
+    offsets: Vec<u32>,
+    /// Number of neighbor clusters for each cluster
+    counts: Vec<u32>,
+    /// Total number of clusters (`ceil(max_units / CLUSTER_SIZE)`)
+    cluster_count: usize,
+}
+
+impl ClusterNeighborList {
+    /// Create a new cluster neighbor list sized for `max_units`
+    pub fn new(max_units: usize) -> Self {
+        let cluster_count = (max_units + CLUSTER_SIZE - 1) / CLUSTER_SIZE;
+        Self {
+            neighbors: Vec::with_capacity(cluster_count * 4),
+            offsets: vec![0; cluster_count],
+            counts: vec![0; cluster_count],
+            cluster_count,
+        }
+    }
+
+    /// Total number of clusters
+    #[inline]
+    pub fn cluster_count(&self) -> usize {
+        self.cluster_count
+    }
+
+    /// Clear the list for reuse
+    pub fn clear(&mut self) {
+        self.neighbors.clear();
+    }
+
+    /// Get the neighbor cluster indices for a given cluster
+    #[inline]
+    pub fn get_cluster_neighbors(&self, cluster_idx: usize) -> &[u32] {
+        let offset = self.offsets[cluster_idx] as usize;
+        let count = self.counts[cluster_idx] as usize;
+        &self.neighbors[offset..offset + count]
+    }
+
+    /// Build the cluster adjacency list using the same uniform spatial hash
+    /// grid as `NeighborList::build_from_grid`: cluster A is adjacent to
+    /// cluster B (including A == B, covering intra-cluster pairs) if any
+    /// live member of A shares a 3x3 grid neighborhood with any live member
+    /// of B on the same layer.
+    pub fn build_from_grid(&mut self, buffer: &BoidsBuffer, cell_size: f32) {
+        self.clear();
+
+        for c in 0..self.cluster_count {
+            self.offsets[c] = 0;
+            self.counts[c] = 0;
+        }
+
+        let count = buffer.len();
+        if count == 0 {
+            return;
+        }
+
+        let grid = UniformGrid::build(buffer, cell_size.max(0.0001));
+
+        // Scratch dedupe set, reused across clusters: `touched` records which
+        // entries of `seen` were set so they can be cheaply reset afterward
+        // instead of clearing the whole vector every cluster.
+        let mut seen = vec![false; self.cluster_count];
+        let mut touched: Vec<usize> = Vec::with_capacity(16);
+
+        unsafe {
+            for cluster_idx in 0..self.cluster_count {
+                self.offsets[cluster_idx] = self.neighbors.len() as u32;
+                touched.clear();
+
+                let base = cluster_idx * CLUSTER_SIZE;
+                for lane in 0..CLUSTER_SIZE {
+                    let unit_idx = base + lane;
+                    if unit_idx >= count {
+                        break;
+                    }
+                    if *buffer.states.add(unit_idx) == UnitState::Dead as u8 {
+                        continue;
+                    }
+
+                    let layer = *buffer.layers.add(unit_idx);
+                    let x = *buffer.positions_x.add(unit_idx);
+                    let y = *buffer.positions_y.add(unit_idx);
+                    let (cx, cy) = grid.cell_of(x, y);
+
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            for candidate in grid.bucket(cx + dx, cy + dy) {
+                                let candidate = candidate as usize;
+                                let candidate_layer = *buffer.layers.add(candidate);
+                                if candidate_layer != layer {
+                                    continue;
+                                }
+
+                                let neighbor_cluster = candidate / CLUSTER_SIZE;
+                                if !seen[neighbor_cluster] {
+                                    seen[neighbor_cluster] = true;
+                                    touched.push(neighbor_cluster);
+                                    self.neighbors.push(neighbor_cluster as u32);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                self.counts[cluster_idx] = (self.neighbors.len() as u32) - self.offsets[cluster_idx];
+                for &c in &touched {
+                    seen[c] = false;
+                }
+            }
+        }
+    }
+}
+
+/// Static circular obstacles that units steer away from
+///
+/// Populated the same way as the old neighbor buffers: JS grabs `data_ptr`,
+/// writes packed `(x, y, radius)` triples, then calls `set_count`.
+pub struct ObstacleList {
+    /// Flat (x, y, radius) triples, length `capacity * 3`
+    data: Vec<f32>,
+    /// Number of obstacles currently populated
+    count: usize,
+    /// Max obstacles the buffer was sized for
+    capacity: usize,
+}
+
+impl ObstacleList {
+    /// Create a new obstacle list with room for `max_obstacles`
+    pub fn new(max_obstacles: usize) -> Self {
+        Self {
+            data: vec![0.0; max_obstacles * 3],
+            count: 0,
+            capacity: max_obstacles,
+        }
+    }
+
+    /// Number of obstacles currently populated
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Check if there are no obstacles
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Get raw pointer to the packed (x, y, radius) array for JS interop
+    #[inline]
+    pub fn data_ptr_mut(&mut self) -> *mut f32 {
+        self.data.as_mut_ptr()
+    }
+
+    /// Set the number of obstacles (after JS populates the array)
+    #[inline]
+    pub fn set_count(&mut self, count: usize) {
+        debug_assert!(count <= self.capacity, "Obstacle count exceeds capacity");
+        self.count = count;
+    }
+
+    /// Get the `(x, y, radius)` of the obstacle at `index`
+    #[inline]
+    pub fn get(&self, index: usize) -> (f32, f32, f32) {
+        debug_assert!(index < self.count, "Obstacle index out of bounds");
+        let base = index * 3;
+        (self.data[base], self.data[base + 1], self.data[base + 2])
+    }
+}
+
+/// 8-connected neighbor offsets, cardinal directions first so that tie
+/// breaking in `FlowField::build` prefers them over diagonals -- this keeps
+/// flow directions from cutting across a corner where two impassable cells
+/// touch only diagonally.
+const FLOW_FIELD_NEIGHBOR_OFFSETS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Grid-based goal-seeking flow field
+///
+/// Populated the same way as `ObstacleList`: JS grabs `passable_ptr_mut`,
+/// writes a row-major `(y * width + x)` passability mask, writes packed
+/// `(x, y)` goal cell coordinates through `goal_cells_ptr_mut`, calls
+/// `set_goal_count`, then `build`. `build` runs a single multi-source BFS
+/// from every goal cell outward over passable cells and precomputes, per
+/// cell, a unit direction vector toward the lowest-distance neighbor --
+/// so sampling a unit's steering direction at runtime (see
+/// `scalar::compute_seek_force`) is just one grid lookup, no per-tick
+/// pathfinding.
+pub struct FlowField {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    origin_x: f32,
+    origin_y: f32,
+    /// Row-major passability mask, `!= 0` means passable
+    passable: Vec<u8>,
+    /// Packed `(x, y)` goal cell coordinates, length `max_goals * 2`
+    goal_cells: Vec<u32>,
+    goal_count: usize,
+    /// BFS hop distance from the nearest goal; `u32::MAX` for impassable or
+    /// unreached cells
+    distance: Vec<u32>,
+    /// Precomputed unit direction vector per cell, toward the
+    /// lowest-distance 8-connected neighbor. Zero for goal cells and cells
+    /// with distance `u32::MAX`.
+    dir_x: Vec<f32>,
+    dir_y: Vec<f32>,
+}
+
+impl FlowField {
+    /// Create a flow field over a `width x height` grid of `cell_size`
+    /// world units, with its `(0, 0)` cell's min corner at
+    /// `(origin_x, origin_y)`. `max_goals` bounds how many goal cells a
+    /// single `build` can seed.
+    pub fn new(width: usize, height: usize, cell_size: f32, origin_x: f32, origin_y: f32, max_goals: usize) -> Self {
+        let cells = width * height;
+        Self {
+            width,
+            height,
+            cell_size,
+            origin_x,
+            origin_y,
+            passable: vec![1u8; cells],
+            goal_cells: vec![0u32; max_goals * 2],
+            goal_count: 0,
+            distance: vec![u32::MAX; cells],
+            dir_x: vec![0.0; cells],
+            dir_y: vec![0.0; cells],
+        }
+    }
+
+    /// Get raw pointer to the row-major passability mask for JS interop
+    #[inline]
+    pub fn passable_ptr_mut(&mut self) -> *mut u8 {
+        self.passable.as_mut_ptr()
+    }
+
+    /// Get raw pointer to the packed (x, y) goal cell array for JS interop
+    #[inline]
+    pub fn goal_cells_ptr_mut(&mut self) -> *mut u32 {
+        self.goal_cells.as_mut_ptr()
+    }
+
+    /// Set the number of goal cells (after JS populates the array)
+    #[inline]
+    pub fn set_goal_count(&mut self, count: usize) {
+        debug_assert!(count <= self.goal_cells.len() / 2, "Goal count exceeds capacity");
+        self.goal_count = count;
+    }
+
+    #[inline]
+    fn index(&self, cx: usize, cy: usize) -> usize {
+        cy * self.width + cx
+    }
+
+    /// Run the multi-source BFS from the configured goal cells across the
+    /// configured passability mask, then precompute each reached cell's flow
+    /// direction. Call after `passable`/goal cells are (re)populated.
+    pub fn build(&mut self) {
+        self.distance.iter_mut().for_each(|d| *d = u32::MAX);
+        self.dir_x.iter_mut().for_each(|d| *d = 0.0);
+        self.dir_y.iter_mut().for_each(|d| *d = 0.0);
+
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+        for g in 0..self.goal_count {
+            let gx = self.goal_cells[g * 2] as usize;
+            let gy = self.goal_cells[g * 2 + 1] as usize;
+            if gx >= self.width || gy >= self.height {
+                continue;
+            }
+            let idx = self.index(gx, gy);
+            if self.passable[idx] == 0 || self.distance[idx] != u32::MAX {
+                continue;
+            }
+            self.distance[idx] = 0;
+            queue.push_back((gx, gy));
+        }
+
+        // Single BFS sweep: a FIFO queue over an unweighted 8-connected grid
+        // visits cells in non-decreasing distance order, so the first time a
+        // cell is reached is its shortest hop distance.
+        while let Some((cx, cy)) = queue.pop_front() {
+            let d = self.distance[self.index(cx, cy)];
+            for &(dx, dy) in &FLOW_FIELD_NEIGHBOR_OFFSETS {
+                let nx = cx as i32 + dx;
+                let ny = cy as i32 + dy;
+                if nx < 0 || ny < 0 || nx as usize >= self.width || ny as usize >= self.height {
+                    continue;
+                }
+                let (nx, ny) = (nx as usize, ny as usize);
+                let nidx = self.index(nx, ny);
+                if self.passable[nidx] == 0 || self.distance[nidx] != u32::MAX {
+                    continue;
+                }
+                self.distance[nidx] = d + 1;
+                queue.push_back((nx, ny));
+            }
+        }
+
+        for cy in 0..self.height {
+            for cx in 0..self.width {
+                let idx = self.index(cx, cy);
+                if self.distance[idx] == 0 || self.distance[idx] == u32::MAX {
+                    continue;
+                }
+
+                // Cardinal offsets come first in FLOW_FIELD_NEIGHBOR_OFFSETS,
+                // and `<` (not `<=`) only replaces the current best on a
+                // strict improvement, so a tie keeps whichever direction was
+                // checked first -- i.e. prefers cardinal over diagonal.
+                let mut best_dist = self.distance[idx];
+                let mut best_dir = None;
+                for &(dx, dy) in &FLOW_FIELD_NEIGHBOR_OFFSETS {
+                    let nx = cx as i32 + dx;
+                    let ny = cy as i32 + dy;
+                    if nx < 0 || ny < 0 || nx as usize >= self.width || ny as usize >= self.height {
+                        continue;
+                    }
+                    let nidx = self.index(nx as usize, ny as usize);
+                    let nd = self.distance[nidx];
+                    if nd < best_dist {
+                        best_dist = nd;
+                        best_dir = Some((dx, dy));
+                    }
+                }
+
+                if let Some((dx, dy)) = best_dir {
+                    let len = ((dx * dx + dy * dy) as f32).sqrt();
+                    self.dir_x[idx] = dx as f32 / len;
+                    self.dir_y[idx] = dy as f32 / len;
+                }
+            }
+        }
+    }
+
+    /// Sample the flow direction at a world position; `None` if the
+    /// position falls outside the grid, or its cell is impassable/unreached
+    /// (callers should treat that as zero seek force, not a fallback value).
+    pub fn sample_direction(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let cx = ((x - self.origin_x) / self.cell_size).floor();
+        let cy = ((y - self.origin_y) / self.cell_size).floor();
+        if cx < 0.0 || cy < 0.0 {
+            return None;
+        }
+        let (cx, cy) = (cx as usize, cy as usize);
+        if cx >= self.width || cy >= self.height {
+            return None;
+        }
+
+        let idx = self.index(cx, cy);
+        if self.distance[idx] == u32::MAX {
+            return None;
+        }
+        Some((self.dir_x[idx], self.dir_y[idx]))
+    }
+}
+
+/// Uniform spatial hash grid used for broad-phase neighbor queries.
+///
+/// Units are binned by `(floor(x / cell_size), floor(y / cell_size))`, hashed
+/// into a flat bucket table, and a counting sort produces CSR-style
+/// `cell_start`/`cell_end` offsets so the 3x3 neighborhood of any cell can be
+/// scanned as a contiguous slice with no per-query allocation. Because
+/// distinct cells can hash into the same bucket, every entry also carries its
+/// real `(cx, cy)` alongside the unit index so `bucket` can filter out
+/// collisions instead of returning units from an unrelated cell.
+struct UniformGrid {
+    /// Start offset into `sorted_units` for each hash bucket (length
+    /// `table_size + 1`, so `cell_start[h]..cell_start[h + 1]` is bucket `h`).
+    cell_start: Vec<u32>,
+    /// Unit indices sorted by bucket
+    sorted_units: Vec<u32>,
+    /// Real cell x-coordinate of `sorted_units[i]`, parallel to it -- lets
+    /// `bucket` reject hash collisions from a different cell
+    sorted_cell_x: Vec<i32>,
+    /// Real cell y-coordinate of `sorted_units[i]`, parallel to it
+    sorted_cell_y: Vec<i32>,
+    /// Number of buckets in the hash table (always a power of two)
+    table_size: usize,
+    /// 1.0 / cell_size, cached to turn divisions into multiplications
+    inv_cell_size: f32,
+}
+
+impl UniformGrid {
+    fn build(buffer: &BoidsBuffer, cell_size: f32) -> Self {
+        let count = buffer.len();
+        // Oversize the table relative to unit count to keep bucket chains short.
+        let table_size = (count.max(1) * 2).next_power_of_two();
+        let mask = (table_size - 1) as u32;
+        let inv_cell_size = 1.0 / cell_size;
+
+        let mut hashes = vec![u32::MAX; count];
+        // Real (cx, cy) per unit, parallel to `hashes`; carried through the
+        // counting sort below so `bucket` can reject hash collisions.
+        let mut cells = vec![(0i32, 0i32); count];
+        // bucket_counts[h] is the number of units hashing to bucket h; after
+        // the prefix sum below it becomes cell_start.
+        let mut bucket_counts = vec![0u32; table_size + 1];
+
+        unsafe {
+            for i in 0..count {
+                if *buffer.states.add(i) == UnitState::Dead as u8 {
+                    continue;
+                }
+
+                let x = *buffer.positions_x.add(i);
+                let y = *buffer.positions_y.add(i);
+                let cx = (x * inv_cell_size).floor() as i32;
+                let cy = (y * inv_cell_size).floor() as i32;
+                let h = Self::hash_cell(cx, cy) & mask;
+
+                hashes[i] = h;
+                cells[i] = (cx, cy);
+                bucket_counts[h as usize + 1] += 1;
+            }
+        }
+
+        // Prefix sum turns per-bucket counts into CSR start offsets.
+        for h in 0..table_size {
+            bucket_counts[h + 1] += bucket_counts[h];
+        }
+        let cell_start = bucket_counts;
+
+        // Scatter unit indices (and their real cell) into their bucket's
+        // slice using a write cursor per bucket, seeded from cell_start (the
+        // classic counting sort).
+        let mut cursor = cell_start.clone();
+        let mut sorted_units = vec![0u32; count];
+        let mut sorted_cell_x = vec![0i32; count];
+        let mut sorted_cell_y = vec![0i32; count];
+        for i in 0..count {
+            let h = hashes[i];
+            if h == u32::MAX {
+                continue;
+            }
+            let slot = cursor[h as usize] as usize;
+            sorted_units[slot] = i as u32;
+            let (cx, cy) = cells[i];
+            sorted_cell_x[slot] = cx;
+            sorted_cell_y[slot] = cy;
+            cursor[h as usize] += 1;
+        }
+
+        Self {
+            cell_start,
+            sorted_units,
+            sorted_cell_x,
+            sorted_cell_y,
+            table_size,
+            inv_cell_size,
+        }
+    }
+
+    /// Hash a grid cell coordinate into a bucket index (pre-mask)
+    #[inline]
+    fn hash_cell(cx: i32, cy: i32) -> u32 {
+        (cx as u32)
+            .wrapping_mul(0x9E3779B1)
+            .wrapping_add((cy as u32).wrapping_mul(0x85EBCA77))
+    }
+
+    #[inline]
+    fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x * self.inv_cell_size).floor() as i32,
+            (y * self.inv_cell_size).floor() as i32,
+        )
+    }
+
+    /// Units actually in cell `(cx, cy)`.
+    ///
+    /// Scans the hash bucket `(cx, cy)` maps to, but filters each entry
+    /// against its stored real cell first -- a distinct cell that happens to
+    /// collide into the same bucket contributes no candidates, rather than
+    /// silently handing back units from an unrelated, possibly far-away cell.
+    #[inline]
+    fn bucket(&self, cx: i32, cy: i32) -> impl Iterator<Item = u32> + '_ {
+        let h = (Self::hash_cell(cx, cy) & (self.table_size as u32 - 1)) as usize;
+        let start = self.cell_start[h] as usize;
+        let end = self.cell_start[h + 1] as usize;
+        (start..end).filter_map(move |i| {
+            if self.sorted_cell_x[i] == cx && self.sorted_cell_y[i] == cy {
+                Some(self.sorted_units[i])
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_creation() {
+        let buffer = BoidsBuffer::new(100);
+        assert_eq!(buffer.capacity(), 100);
+        assert_eq!(buffer.len(), 0);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_buffer_alignment() {
+        let buffer = BoidsBuffer::new(100);
+        // Check that pointers are 16-byte aligned
+        assert_eq!(buffer.positions_x as usize % 16, 0);
+        assert_eq!(buffer.positions_y as usize % 16, 0);
+        assert_eq!(buffer.velocities_x as usize % 16, 0);
+        assert_eq!(buffer.velocities_y as usize % 16, 0);
+    }
+
+    #[test]
+    fn test_capacity_rounding() {
+        // Capacity should round up to multiple of 4
+        let buffer = BoidsBuffer::new(1);
+        assert_eq!(buffer.capacity(), 4);
+
+        let buffer = BoidsBuffer::new(5);
+        assert_eq!(buffer.capacity(), 8);
+
+        let buffer = BoidsBuffer::new(8);
+        assert_eq!(buffer.capacity(), 8);
+    }
+
+    #[test]
+    fn test_try_new_succeeds_for_ordinary_capacity() {
+        let buffer = BoidsBuffer::try_new(100).expect("ordinary capacity should allocate fine");
+        assert_eq!(buffer.capacity(), 100);
+    }
+
+    #[test]
+    fn test_try_new_rejects_capacity_overflow() {
+        // usize::MAX rounds up past usize::MAX before it ever reaches the
+        // allocator, and `usize::MAX / 4` times 4 bytes overflows the
+        // Layout's own size computation either way.
+        let err = BoidsBuffer::try_new(usize::MAX).unwrap_err();
+        assert_eq!(err, BufferAllocError::CapacityOverflow);
+    }
+
+    #[test]
+    fn test_with_lanes_rounds_capacity_and_aligns_to_lane_width() {
+        let buffer = BoidsBuffer::with_lanes(5, 8);
+        assert_eq!(buffer.lanes(), 8);
+        assert_eq!(buffer.capacity(), 8);
+        assert_eq!(buffer.positions_x as usize % 32, 0);
+
+        let buffer = BoidsBuffer::with_lanes(8, 8);
+        assert_eq!(buffer.capacity(), 8);
+    }
+
+    #[test]
+    fn test_new_still_defaults_to_four_lanes() {
+        let buffer = BoidsBuffer::new(5);
+        assert_eq!(buffer.lanes(), 4);
+        assert_eq!(buffer.capacity(), 8);
+        assert_eq!(buffer.positions_x as usize % 16, 0);
+    }
+
+    #[test]
+    fn test_try_with_lanes_rejects_unsupported_width() {
+        let err = BoidsBuffer::try_with_lanes(16, 3).unwrap_err();
+        assert_eq!(err, BufferAllocError::UnsupportedLaneWidth(3));
+    }
+
+    #[test]
+    fn test_reserve_slots_preserves_lane_width_after_growth() {
+        let mut buffer = BoidsBuffer::with_lanes(4, 8);
+        buffer.reserve_slots(20);
+        assert_eq!(buffer.lanes(), 8);
+        assert_eq!(buffer.capacity() % 8, 0);
+        assert_eq!(buffer.positions_x as usize % 32, 0);
+    }
+
+    #[test]
+    fn test_alloc_slot_reuses_freed_index_before_extending() {
+        let mut buffer = BoidsBuffer::new(8);
+        let a = buffer.alloc_slot();
+        let b = buffer.alloc_slot();
+        let c = buffer.alloc_slot();
+        assert_eq!((a, b, c), (0, 1, 2));
+        assert_eq!(buffer.len(), 3);
+
+        unsafe { buffer.free_slot(b) };
+        assert_eq!(unsafe { *buffer.states.add(b) }, UnitState::Dead as u8);
+
+        // The freed slot `b` is reused before the allocator extends the
+        // high-water mark to a fresh index.
+        let reused = buffer.alloc_slot();
+        assert_eq!(reused, b);
+
+        let fresh = buffer.alloc_slot();
+        assert_eq!(fresh, 3);
+        assert_eq!(buffer.len(), 4);
+    }
+
+    #[test]
+    fn test_alloc_slot_never_exceeds_capacity() {
+        let mut buffer = BoidsBuffer::new(4);
+        for _ in 0..4 {
+            buffer.alloc_slot();
+        }
+        assert_eq!(buffer.len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "no free slots")]
+    fn test_alloc_slot_panics_once_capacity_is_exhausted() {
+        let mut buffer = BoidsBuffer::new(4);
+        for _ in 0..5 {
+            buffer.alloc_slot();
+        }
+    }
+
+    #[test]
+    fn test_reserve_slots_grows_capacity_and_preserves_live_data() {
+        let mut buffer = BoidsBuffer::new(4);
+        let slot = buffer.alloc_slot();
+        unsafe {
+            *buffer.positions_x.add(slot) = 42.0;
+        }
+
+        buffer.reserve_slots(100);
+        assert!(buffer.capacity() >= 100);
+        assert_eq!(buffer.capacity() % 4, 0);
+        unsafe {
+            assert_eq!(*buffer.positions_x.add(slot), 42.0);
+        }
+
+        // The grown region is fresh, unoccupied capacity.
+        let next = buffer.alloc_slot();
+        assert_ne!(next, slot);
+    }
+
+    #[test]
+    fn test_reserve_slots_is_a_no_op_within_existing_capacity() {
+        let mut buffer = BoidsBuffer::new(64);
+        buffer.reserve_slots(10);
+        assert_eq!(buffer.capacity(), 64);
+    }
+
+    #[test]
+    fn test_reserve_doubles_capacity_instead_of_growing_to_the_exact_request() {
+        let mut buffer = BoidsBuffer::new(4);
+        buffer.reserve(1);
+        // `count` is 0, so `additional` alone would round up to 4; doubling
+        // the existing capacity (4 -> 8) wins instead.
+        assert_eq!(buffer.capacity(), 8);
+    }
+
+    #[test]
+    fn test_reserve_grows_past_doubling_when_additional_demands_more() {
+        let mut buffer = BoidsBuffer::new(4);
+        buffer.reserve(100);
+        assert!(buffer.capacity() >= 100);
+    }
+
+    #[test]
+    fn test_reserve_preserves_live_data_and_zeroes_the_new_tail() {
+        let mut buffer = BoidsBuffer::new(4);
+        buffer.set_count(4);
+        unsafe {
+            *buffer.positions_x.add(0) = 1.0;
+            *buffer.positions_x.add(3) = 4.0;
+        }
+
+        buffer.reserve(20);
+        assert!(buffer.capacity() >= 24);
+        unsafe {
+            assert_eq!(*buffer.positions_x.add(0), 1.0);
+            assert_eq!(*buffer.positions_x.add(3), 4.0);
+            assert_eq!(*buffer.positions_x.add(buffer.capacity() - 1), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_try_reserve_rejects_capacity_overflow() {
+        let mut buffer = BoidsBuffer::new(4);
+        let err = buffer.try_reserve(usize::MAX).unwrap_err();
+        assert_eq!(err, BufferAllocError::CapacityOverflow);
+    }
+
+    #[test]
+    fn test_try_grow_to_is_a_no_op_within_existing_capacity() {
+        let mut buffer = BoidsBuffer::new(64);
+        buffer.try_grow_to(10).expect("no-op grow should not error");
+        assert_eq!(buffer.capacity(), 64);
+    }
+
+    #[test]
+    fn test_clear_frees_every_slot_allocated_through_the_bitmap() {
+        let mut buffer = BoidsBuffer::new(8);
+        buffer.alloc_slot();
+        buffer.alloc_slot();
+        buffer.clear();
+        assert_eq!(buffer.len(), 0);
+        assert_eq!(buffer.alloc_slot(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_matches_force_output() {
+        let mut original = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            *original.positions_x.add(0) = 0.0;
+            *original.positions_y.add(0) = 0.0;
+            *original.velocities_x.add(0) = 1.0;
+            *original.radii.add(0) = 0.5;
+            *original.states.add(0) = UnitState::Active as u8;
+            *original.layers.add(0) = 0;
+
+            *original.positions_x.add(1) = 0.5;
+            *original.positions_y.add(1) = 0.0;
+            *original.radii.add(1) = 0.5;
+            *original.states.add(1) = UnitState::Active as u8;
+            *original.layers.add(1) = 0;
+        }
+        original.set_count(2);
+
+        neighbors.begin_unit(0);
+        neighbors.add_neighbor(0, 1);
+        neighbors.begin_unit(1);
+        neighbors.add_neighbor(1, 0);
+
+        let bytes = original.serialize();
+        let mut restored = BoidsBuffer::deserialize(&bytes).expect("round-trip should succeed");
+
+        assert_eq!(restored.len(), original.len());
+        unsafe {
+            assert_eq!(restored.get_position(0), original.get_position(0));
+            assert_eq!(restored.get_position(1), original.get_position(1));
+            assert_eq!(restored.get_velocity(0), original.get_velocity(0));
+        }
+
+        let params = crate::simd::BoidsParams::default();
+        crate::scalar::compute_all_forces(&mut original, &neighbors, &params);
+        crate::scalar::compute_all_forces(&mut restored, &neighbors, &params);
+
+        unsafe {
+            assert_eq!(original.get_separation_force(0), restored.get_separation_force(0));
+            assert_eq!(original.get_separation_force(1), restored.get_separation_force(1));
+        }
+    }
+
+    #[test]
+    fn test_deserialize_marks_restored_units_occupied_before_alloc_slot() {
+        // `deserialize` sets `count` directly but the occupied bitmap isn't
+        // part of the snapshot format, so it has to be rebuilt from `count`
+        // -- otherwise `alloc_slot` would think every restored slot is free
+        // and hand one straight back out, clobbering a live restored unit.
+        let mut original = BoidsBuffer::new(4);
+        original.alloc_slot();
+        original.alloc_slot();
+        original.alloc_slot();
+        assert_eq!(original.len(), 3);
+
+        let bytes = original.serialize();
+        let mut restored = BoidsBuffer::deserialize(&bytes).expect("round-trip should succeed");
+
+        assert_eq!(restored.alloc_slot(), 3, "alloc_slot should skip the 3 restored live units");
+    }
+
+    #[test]
+    fn test_deserialize_rejects_bad_magic() {
+        // `BoidsBuffer` owns raw pointers with a manual `Drop`, so it isn't
+        // `PartialEq` and `Result<BoidsBuffer, _>` can't use `assert_eq!`;
+        // compare the `unwrap_err()` value instead.
+        let mut bytes = BoidsBuffer::new(4).serialize();
+        bytes[0] = b'X';
+        assert_eq!(BoidsBuffer::deserialize(&bytes).unwrap_err(), SnapshotError::BadMagic);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_truncated_buffer() {
+        let bytes = BoidsBuffer::new(4).serialize();
+        assert_eq!(
+            BoidsBuffer::deserialize(&bytes[..bytes.len() - 1]).unwrap_err(),
+            SnapshotError::Truncated
+        );
+    }
+
+    #[test]
+    fn test_deserialize_rejects_count_exceeding_capacity() {
+        let mut bytes = BoidsBuffer::new(4).serialize();
+        // Header count field is bytes[8..12]; forge a count larger than capacity.
+        bytes[8..12].copy_from_slice(&99u32.to_le_bytes());
+        assert_eq!(
+            BoidsBuffer::deserialize(&bytes).unwrap_err(),
+            SnapshotError::CountExceedsCapacity { count: 99, capacity: 4 }
+        );
+    }
+
+    #[test]
+    fn test_neighbor_list() {
+        let mut list = NeighborList::new(10);
+
+        list.begin_unit(0);
+        list.add_neighbor(0, 1);
+        list.add_neighbor(0, 2);
+        list.add_neighbor(0, 3);
+
+        list.begin_unit(1);
+        list.add_neighbor(1, 0);
+        list.add_neighbor(1, 2);
+
+        assert_eq!(list.neighbor_count(0), 3);
+        assert_eq!(list.neighbor_count(1), 2);
+        assert_eq!(list.get_neighbors(0), &[1, 2, 3]);
+        assert_eq!(list.get_neighbors(1), &[0, 2]);
+    }
+
+    #[test]
+    fn test_try_add_neighbor_drops_once_max_is_reached() {
+        let mut list = NeighborList::new(4);
+        list.set_max_neighbors_per_unit(2);
+
+        list.begin_unit(0);
+        assert!(list.try_add_neighbor(0, 1).is_ok());
+        assert!(list.try_add_neighbor(0, 2).is_ok());
+        let err = list.try_add_neighbor(0, 3).unwrap_err();
+        assert_eq!(err, NeighborOverflow { unit_index: 0, neighbor_index: 3 });
+
+        assert_eq!(list.neighbor_count(0), 2);
+        assert_eq!(list.get_neighbors(0), &[1, 2]);
+    }
+
+    #[test]
+    fn test_add_neighbor_nearest_with_validity_converges_to_k_nearest() {
+        let mut list = NeighborList::new(4);
+        list.set_max_neighbors_per_unit(2);
+
+        list.begin_unit(0);
+        // Distances 9.0, 4.0, 1.0, 16.0 for neighbors 1..4 -- the two
+        // closest (4.0 and 1.0, neighbors 2 and 3) should survive regardless
+        // of arrival order.
+        list.add_neighbor_nearest_with_validity(0, 1, true, 9.0);
+        list.add_neighbor_nearest_with_validity(0, 2, true, 4.0);
+        list.add_neighbor_nearest_with_validity(0, 3, true, 1.0);
+        list.add_neighbor_nearest_with_validity(0, 4, true, 16.0);
+
+        assert_eq!(list.neighbor_count(0), 2);
+        let mut survivors = list.get_neighbors(0).to_vec();
+        survivors.sort();
+        assert_eq!(survivors, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_reserve_grows_capacity_without_changing_len() {
+        let mut list = NeighborList::new(4);
+        list.reserve(64);
+        assert!(list.neighbors_ptr() as usize != 0);
+        assert!(list.neighbors.capacity() >= 64);
+        assert_eq!(list.neighbor_count(0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds reserved capacity")]
+    fn test_set_neighbor_count_rejects_count_past_reserved_capacity() {
+        let mut list = NeighborList::new(4);
+        list.set_neighbor_count(1_000_000);
+    }
+
+    #[test]
+    fn test_build_from_grid_half_only_stores_ascending_pairs() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            for i in 0..3 {
+                *buffer.positions_x.add(i) = i as f32 * 0.2;
+                *buffer.positions_y.add(i) = 0.0;
+                *buffer.radii.add(i) = 0.5;
+                *buffer.states.add(i) = UnitState::Active as u8;
+            }
+        }
+        buffer.set_count(3);
+
+        neighbors.build_from_grid_half(&buffer, 5.0);
+
+        assert!(neighbors.is_half());
+        assert_eq!(neighbors.get_neighbors(0), &[1, 2]);
+        assert_eq!(neighbors.get_neighbors(1), &[2]);
+        assert_eq!(neighbors.get_neighbors(2), &[] as &[u32]);
+    }
+
+    #[test]
+    fn test_build_from_grid_precomputes_validity_mask() {
+        let mut buffer = BoidsBuffer::new(4);
+        let mut neighbors = NeighborList::new(4);
+
+        unsafe {
+            for i in 0..3 {
+                *buffer.positions_x.add(i) = i as f32 * 0.2;
+                *buffer.positions_y.add(i) = 0.0;
+                *buffer.radii.add(i) = 0.5;
+                *buffer.states.add(i) = UnitState::Active as u8;
+            }
+            // Unit 2 is gathering, so it's an invalid neighbor for 0 and 1.
+            *buffer.states.add(2) = UnitState::Gathering as u8;
+        }
+        buffer.set_count(3);
+
+        neighbors.build_from_grid(&buffer, 5.0);
+
+        assert_eq!(neighbors.get_neighbors(0), &[1, 2]);
+        assert_eq!(neighbors.get_valid_mask(0), &[-1, 0]);
+        assert_eq!(neighbors.get_neighbors(1), &[0, 2]);
+        assert_eq!(neighbors.get_valid_mask(1), &[-1, 0]);
+    }
+
+    #[test]
+    fn test_cluster_neighbor_list_groups_by_index_block() {
+        let mut buffer = BoidsBuffer::new(8);
+        let mut clusters = ClusterNeighborList::new(8);
+
+        unsafe {
+            // Units 0-3 (cluster 0) clumped near the origin
+            for i in 0..4 {
+                *buffer.positions_x.add(i) = i as f32 * 0.1;
+                *buffer.positions_y.add(i) = 0.0;
+                *buffer.states.add(i) = UnitState::Active as u8;
+            }
+            // Units 4-7 (cluster 1) far away, out of range
+            for i in 4..8 {
+                *buffer.positions_x.add(i) = 1000.0 + i as f32;
+                *buffer.positions_y.add(i) = 1000.0;
+                *buffer.states.add(i) = UnitState::Active as u8;
+            }
+        }
+        buffer.set_count(8);
+
+        clusters.build_from_grid(&buffer, 5.0);
+
+        assert_eq!(clusters.cluster_count(), 2);
+        // Cluster 0 is self-adjacent (intra-cluster pairs) but not adjacent
+        // to the far-away cluster 1.
+        assert_eq!(clusters.get_cluster_neighbors(0), &[0]);
+        assert_eq!(clusters.get_cluster_neighbors(1), &[1]);
+    }
+
+    #[test]
+    fn test_flow_field_points_toward_goal() {
+        // 5-wide row of passable cells, goal at the far right; every other
+        // cell should point one step closer.
+        let mut field = FlowField::new(5, 1, 1.0, 0.0, 0.0, 1);
+
+        unsafe {
+            std::ptr::write_bytes(field.passable_ptr_mut(), 1, 5);
+            *field.goal_cells_ptr_mut().add(0) = 4;
+            *field.goal_cells_ptr_mut().add(1) = 0;
+        }
+        field.set_goal_count(1);
+        field.build();
+
+        let (dx, dy) = field.sample_direction(0.5, 0.5).expect("cell 0 should be reached");
+        assert!((dx - 1.0).abs() < 1e-6, "cell 0 should point right toward the goal, got {dx}");
+        assert_eq!(dy, 0.0);
+
+        // The goal cell itself has no direction to steer toward.
+        let (gdx, gdy) = field.sample_direction(4.5, 0.5).expect("goal cell should be reached");
+        assert_eq!((gdx, gdy), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_flow_field_impassable_cell_unreached() {
+        let mut field = FlowField::new(3, 1, 1.0, 0.0, 0.0, 1);
+
+        unsafe {
+            std::ptr::write_bytes(field.passable_ptr_mut(), 1, 3);
+            // Block the middle cell so cell 0 can never reach the goal at cell 2.
+            *field.passable_ptr_mut().add(1) = 0;
+            *field.goal_cells_ptr_mut().add(0) = 2;
+            *field.goal_cells_ptr_mut().add(1) = 0;
+        }
+        field.set_goal_count(1);
+        field.build();
+
+        assert_eq!(field.sample_direction(0.5, 0.5), None, "cell cut off by the block should be unreached");
+    }
+
+    #[test]
+    fn test_flow_field_out_of_range_sample_is_none() {
+        let mut field = FlowField::new(2, 2, 1.0, 0.0, 0.0, 1);
+
+        unsafe {
+            std::ptr::write_bytes(field.passable_ptr_mut(), 1, 4);
+            *field.goal_cells_ptr_mut().add(0) = 0;
+            *field.goal_cells_ptr_mut().add(1) = 0;
+        }
+        field.set_goal_count(1);
+        field.build();
+
+        assert_eq!(field.sample_direction(-1.0, 0.5), None);
+        assert_eq!(field.sample_direction(5.0, 0.5), None);
+    }
+
+    #[test]
+    fn test_flow_field_prefers_cardinal_over_diagonal_on_tie() {
+        // Goal at (5, 0) on an open 6x4 grid. From (0, 3), the same-row
+        // cardinal neighbor (1, 3) and the diagonal neighbor (1, 2) both sit
+        // at hop distance 4 from the goal -- a genuine tie, since Chebyshev
+        // distance doesn't discount diagonal steps.
+        let mut field = FlowField::new(6, 4, 1.0, 0.0, 0.0, 1);
+
+        unsafe {
+            std::ptr::write_bytes(field.passable_ptr_mut(), 1, 24);
+            *field.goal_cells_ptr_mut().add(0) = 5;
+            *field.goal_cells_ptr_mut().add(1) = 0;
+        }
+        field.set_goal_count(1);
+        field.build();
+
+        let (dx, dy) = field.sample_direction(0.5, 3.5).expect("cell (0, 3) should be reached");
+        // Cardinal offsets are tried first in FLOW_FIELD_NEIGHBOR_OFFSETS, so
+        // a tie should resolve to the same-row step rather than the diagonal one.
+        assert!((dx - 1.0).abs() < 1e-6 && dy == 0.0, "expected a cardinal tie-break, got ({dx}, {dy})");
     }
 }
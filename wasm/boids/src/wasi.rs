@@ -0,0 +1,181 @@
+//! wasm32-wasi host bindings
+//!
+//! `wasm-bindgen`'s glue imports `__wbindgen_*` shims that only a
+//! browser/Node bundler provides, so it can't target `wasm32-wasi`. This
+//! module re-exposes the same engine operations as a plain, `#[no_mangle]`
+//! C ABI instead, so a host runtime (e.g. wasmtime) can drive the flocking
+//! step directly. That makes it possible to run the simulation
+//! server-authoritatively for RTS gameplay or deterministic replay testing,
+//! without a browser in the loop.
+//!
+//! Usage from the host: call `boids_wasi_create` to get a handle, write
+//! unit state through the `boids_wasi_*_ptr` pointers (same layout as the
+//! browser build's typed-array views), then call `boids_wasi_build_neighbors`
+//! and `boids_wasi_step` each tick.
+
+use std::cell::RefCell;
+
+use crate::scalar;
+use crate::simd;
+use crate::soa::{BoidsBuffer, NeighborList, ObstacleList};
+use crate::DEFAULT_MAX_OBSTACLES;
+
+/// Per-handle engine state; the wasi counterpart of the wasm-bindgen `BoidsEngine`
+struct WasiEngine {
+    buffer: BoidsBuffer,
+    neighbors: NeighborList,
+    obstacles: ObstacleList,
+    params: simd::BoidsParams,
+}
+
+thread_local! {
+    // wasm32-wasi is single-threaded, so a thread-local handle table gives
+    // the host stable integer handles without needing a lock.
+    static ENGINES: RefCell<Vec<Option<WasiEngine>>> = RefCell::new(Vec::new());
+}
+
+/// Create a new engine with capacity for `max_units`, returning its handle
+#[no_mangle]
+pub extern "C" fn boids_wasi_create(max_units: usize) -> u32 {
+    let engine = WasiEngine {
+        buffer: BoidsBuffer::new(max_units),
+        neighbors: NeighborList::new(max_units),
+        obstacles: ObstacleList::new(DEFAULT_MAX_OBSTACLES),
+        params: simd::BoidsParams::default(),
+    };
+
+    ENGINES.with(|engines| {
+        let mut engines = engines.borrow_mut();
+        engines.push(Some(engine));
+        (engines.len() - 1) as u32
+    })
+}
+
+/// Destroy an engine and free its handle slot
+#[no_mangle]
+pub extern "C" fn boids_wasi_destroy(handle: u32) {
+    ENGINES.with(|engines| {
+        if let Some(slot) = engines.borrow_mut().get_mut(handle as usize) {
+            *slot = None;
+        }
+    });
+}
+
+/// Set the current unit count (after the host populates the buffers)
+#[no_mangle]
+pub extern "C" fn boids_wasi_set_unit_count(handle: u32, count: usize) {
+    ENGINES.with(|engines| {
+        if let Some(Some(engine)) = engines.borrow_mut().get_mut(handle as usize) {
+            engine.buffer.set_count(count);
+        }
+    });
+}
+
+/// Get pointer to positions X array
+#[no_mangle]
+pub extern "C" fn boids_wasi_positions_x_ptr(handle: u32) -> *mut f32 {
+    with_buffer_ptr(handle, |buffer| buffer.positions_x_ptr())
+}
+
+/// Get pointer to positions Y array
+#[no_mangle]
+pub extern "C" fn boids_wasi_positions_y_ptr(handle: u32) -> *mut f32 {
+    with_buffer_ptr(handle, |buffer| buffer.positions_y_ptr())
+}
+
+/// Get pointer to velocities X array
+#[no_mangle]
+pub extern "C" fn boids_wasi_velocities_x_ptr(handle: u32) -> *mut f32 {
+    with_buffer_ptr(handle, |buffer| buffer.velocities_x_ptr())
+}
+
+/// Get pointer to velocities Y array
+#[no_mangle]
+pub extern "C" fn boids_wasi_velocities_y_ptr(handle: u32) -> *mut f32 {
+    with_buffer_ptr(handle, |buffer| buffer.velocities_y_ptr())
+}
+
+/// Get pointer to radii array
+#[no_mangle]
+pub extern "C" fn boids_wasi_radii_ptr(handle: u32) -> *mut f32 {
+    with_buffer_ptr(handle, |buffer| buffer.radii_ptr())
+}
+
+/// Get pointer to states array (u8)
+#[no_mangle]
+pub extern "C" fn boids_wasi_states_ptr(handle: u32) -> *mut u8 {
+    ENGINES.with(|engines| {
+        match engines.borrow_mut().get_mut(handle as usize) {
+            Some(Some(engine)) => engine.buffer.states_ptr(),
+            _ => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Get pointer to layers array (u8)
+#[no_mangle]
+pub extern "C" fn boids_wasi_layers_ptr(handle: u32) -> *mut u8 {
+    ENGINES.with(|engines| {
+        match engines.borrow_mut().get_mut(handle as usize) {
+            Some(Some(engine)) => engine.buffer.layers_ptr(),
+            _ => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Build the neighbor list from the current positions/radii/layers using an
+/// internal uniform spatial hash grid (see `BoidsEngine::build_neighbors`)
+#[no_mangle]
+pub extern "C" fn boids_wasi_build_neighbors(handle: u32, cell_size: f32) {
+    ENGINES.with(|engines| {
+        if let Some(Some(engine)) = engines.borrow_mut().get_mut(handle as usize) {
+            engine.neighbors.build_from_grid(&engine.buffer, cell_size);
+        }
+    });
+}
+
+/// Compute all boids forces for the current tick (see `BoidsEngine::compute_forces`).
+///
+/// wasm32-wasi is still `wasm32`, so the same f32x4 SIMD kernel
+/// `BoidsEngine::compute_forces` dispatches to in the browser build is
+/// available here too; fall back to `scalar` only when the host doesn't
+/// support it (see `simd::simd_available`).
+#[no_mangle]
+pub extern "C" fn boids_wasi_compute_forces(handle: u32) {
+    ENGINES.with(|engines| {
+        if let Some(Some(engine)) = engines.borrow_mut().get_mut(handle as usize) {
+            if simd::simd_available() {
+                simd::compute_all_forces_simd(&mut engine.buffer, &engine.neighbors, &engine.params);
+                simd::compute_boundary_forces_simd(&mut engine.buffer, &engine.obstacles, &engine.params);
+            } else {
+                scalar::compute_all_forces(&mut engine.buffer, &engine.neighbors, &engine.params);
+                scalar::compute_boundary_forces(&mut engine.buffer, &engine.obstacles, &engine.params);
+            }
+        }
+    });
+}
+
+/// Run one full simulation tick: compute forces, then integrate velocity
+/// and position in-place (see `BoidsEngine::step`)
+#[no_mangle]
+pub extern "C" fn boids_wasi_step(handle: u32, dt: f32) {
+    boids_wasi_compute_forces(handle);
+    ENGINES.with(|engines| {
+        if let Some(Some(engine)) = engines.borrow_mut().get_mut(handle as usize) {
+            if simd::simd_available() {
+                simd::integrate_simd(&mut engine.buffer, &engine.params, dt);
+            } else {
+                scalar::integrate(&mut engine.buffer, &engine.params, dt);
+            }
+        }
+    });
+}
+
+fn with_buffer_ptr(handle: u32, f: impl FnOnce(&BoidsBuffer) -> *mut f32) -> *mut f32 {
+    ENGINES.with(|engines| {
+        match engines.borrow_mut().get_mut(handle as usize) {
+            Some(Some(engine)) => f(&engine.buffer),
+            _ => std::ptr::null_mut(),
+        }
+    })
+}